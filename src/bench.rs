@@ -0,0 +1,199 @@
+use std::fs;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use log::{error, info};
+use serde_json::{json, Value};
+use super::day::DayMaker;
+use super::registry::Registered;
+use super::runner::{self, Input};
+
+pub struct BenchConfig {
+    pub iterations: usize,
+    pub warmup: usize,
+    pub budget: Option<Duration>,
+    pub parts: Vec<usize>,
+}
+
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        let count = samples.len();
+        let nanos: Vec<f64> = samples.iter().map(|sample| sample.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / count as f64;
+        let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (samples[count / 2 - 1] + samples[count / 2]) / 2
+        } else {
+            samples[count / 2]
+        };
+        let p95_rank = ((count as f64 * 0.95).ceil() as usize).max(1); // nearest rank
+        Stats {
+            samples: count,
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean as u64),
+            p95: samples[p95_rank - 1],
+            std_dev: Duration::from_nanos(variance.sqrt() as u64),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "samples": self.samples,
+            "min_ns": self.min.as_nanos() as u64,
+            "median_ns": self.median.as_nanos() as u64,
+            "mean_ns": self.mean.as_nanos() as u64,
+            "p95_ns": self.p95.as_nanos() as u64,
+            "std_dev_ns": self.std_dev.as_nanos() as u64,
+        })
+    }
+}
+
+pub struct DayBench {
+    pub day_num: usize,
+    pub input: String,
+    pub parse: Stats,
+    pub prepare: Stats,
+    pub parts: Vec<(usize, Stats)>,
+    pub total: Stats,
+}
+
+impl DayBench {
+    fn to_json(&self) -> Value {
+        let mut stats = json!({
+            "parse": self.parse.to_json(),
+            "prepare": self.prepare.to_json(),
+            "total": self.total.to_json(),
+        });
+        for (part, part_stats) in self.parts.iter() {
+            stats[format!("part{}", part)] = part_stats.to_json();
+        }
+        json!({
+            "day": self.day_num,
+            "input": self.input,
+            "stats": stats,
+        })
+    }
+}
+
+pub fn bench_day(maker: DayMaker, day_num: usize, input: &Input, config: &BenchConfig) -> Result<DayBench, String> {
+    let content = input.read(day_num)?;
+
+    let mut parse_samples: Vec<Duration> = Vec::new();
+    let mut prepare_samples: Vec<Duration> = Vec::new();
+    let mut part_samples: Vec<Vec<Duration>> = vec![Vec::new(); config.parts.len()];
+    let mut total_samples: Vec<Duration> = Vec::new();
+    let mut start: Option<Instant> = None;
+    for iteration in 0..(config.warmup + config.iterations) {
+        if iteration == config.warmup {
+            start = Some(Instant::now());
+        }
+        let over_budget = match (start, config.budget) {
+            (Some(start), Some(budget)) => start.elapsed() > budget,
+            _ => false
+        };
+        if over_budget && !total_samples.is_empty() {
+            break;
+        }
+
+        let run = runner::guarded_run(maker, &content, &config.parts, None)
+            .map_err(|desc| format!("{}: {}", input.path(day_num), desc))?;
+        if iteration < config.warmup {
+            continue;
+        }
+        parse_samples.push(run.parse_time);
+        prepare_samples.push(run.prepare_time);
+        for (samples, part_run) in part_samples.iter_mut().zip(run.parts.iter()) {
+            samples.push(part_run.time);
+        }
+        total_samples.push(run.total_time());
+    }
+
+    if total_samples.is_empty() {
+        return Err(String::from("no samples taken"));
+    }
+    Ok(DayBench {
+        day_num,
+        input: input.name(),
+        parse: Stats::from_samples(parse_samples),
+        prepare: Stats::from_samples(prepare_samples),
+        parts: config.parts.iter().cloned()
+            .zip(part_samples.into_iter().map(Stats::from_samples))
+            .collect(),
+        total: Stats::from_samples(total_samples),
+    })
+}
+
+pub fn bench_days(days: &[Registered], input: &Input, config: &BenchConfig, json_path: Option<&str>) -> bool {
+    let mut all_ok = true;
+    let mut results: Vec<Value> = Vec::new();
+    println!("{:>3}  {:<7}  {:>7}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+             "day", "stage", "samples", "min", "median", "mean", "p95", "std dev");
+    for registered in days {
+        let day_num = registered.day;
+        match bench_day(registered.maker, day_num, input, config) {
+            Ok(bench) => {
+                let mut rows = vec![(String::from("parse"), &bench.parse), (String::from("prepare"), &bench.prepare)];
+                for (part, stats) in bench.parts.iter() {
+                    rows.push((format!("part {}", part), stats));
+                }
+                rows.push((String::from("total"), &bench.total));
+                for (stage, stats) in rows {
+                    println!("{:>3}  {:<7}  {:>7}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+                             day_num, stage, stats.samples,
+                             format!("{:?}", stats.min),
+                             format!("{:?}", stats.median),
+                             format!("{:?}", stats.mean),
+                             format!("{:?}", stats.p95),
+                             format!("{:?}", stats.std_dev));
+                }
+                results.push(bench.to_json());
+            }
+            Err(desc) => {
+                all_ok = false;
+                println!("{:>3}  FAILED: {}", day_num, desc);
+                results.push(json!({
+                    "day": day_num,
+                    "input": input.name(),
+                    "error": desc,
+                }));
+            }
+        }
+    }
+
+    if let Some(json_path) = json_path {
+        let report = json!({
+            "revision": git_revision(),
+            "iterations": config.iterations,
+            "warmup": config.warmup,
+            "budget_ms": config.budget.map(|budget| budget.as_millis() as u64),
+            "days": results,
+        });
+        match fs::write(json_path, serde_json::to_string_pretty(&report).unwrap()) {
+            Ok(_) => info!("Wrote bench results to {}", json_path),
+            Err(error) => {
+                error!("Unable to write bench results to {}: {}", json_path, error);
+                all_ok = false;
+            }
+        }
+    }
+    all_ok
+}
+
+fn git_revision() -> Option<String> {
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,150 @@
+use std::fs;
+use std::io;
+use log::warn;
+use sha2::{Digest, Sha256};
+use super::day::Answer;
+use super::registry::Registered;
+use super::runner::{PartRun, PARTS};
+use super::verify::{self, AnswerKey};
+
+pub const DEFAULT_CACHE_DIR: &str = ".cache";
+const CACHE_KEY: &str = "cached";
+
+// answers already worked out, one answers.txt style file per (year, day, solver version, input hash), eg
+//   .cache/2021/day19-v1-<sha256 of the input>.txt
+pub struct Cache {
+    dir: String
+}
+
+impl Cache {
+    pub fn new(dir: &str) -> Cache {
+        Cache {
+            dir: String::from(dir)
+        }
+    }
+
+    fn path(&self, registered: &Registered, content: &str) -> String {
+        format!("{}/{}/day{:02}-v{}-{:x}.txt",
+                self.dir, registered.year, registered.day, registered.version, Sha256::digest(content.as_bytes()))
+    }
+
+    fn load_key(&self, registered: &Registered, content: &str) -> Option<AnswerKey> {
+        let path = self.path(registered, content);
+        let file_content = match fs::read_to_string(&path) {
+            Ok(file_content) => file_content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                warn!("Ignoring cache file {}: {}", path, error);
+                return None;
+            }
+        };
+        match AnswerKey::from_content(&file_content) {
+            Ok(key) => Some(key),
+            Err(desc) => {
+                warn!("Ignoring cache file {}: {}", path, desc);
+                None
+            }
+        }
+    }
+
+    // the cached answer for each part, None for the parts never worked out
+    pub fn load(&self, registered: &Registered, content: &str, parts: &[usize]) -> Vec<Option<Answer>> {
+        let key = self.load_key(registered, content);
+        parts.iter()
+            .map(|part| key.as_ref().and_then(|key| key.expected(CACHE_KEY, *part)).cloned())
+            .collect()
+    }
+
+    // adds the runs to whatever was already cached for the input
+    pub fn store(&self, registered: &Registered, content: &str, part_runs: &[PartRun]) -> Result<(), String> {
+        let key = self.load_key(registered, content);
+        let entries: String = PARTS.iter()
+            .filter_map(|part| part_runs.iter()
+                .find(|part_run| part_run.part == *part)
+                .map(|part_run| &part_run.answer)
+                .or_else(|| key.as_ref().and_then(|key| key.expected(CACHE_KEY, *part)))
+                .map(|answer| verify::format_entry(CACHE_KEY, *part, answer)))
+            .collect();
+
+        let dir = format!("{}/{}", self.dir, registered.year);
+        fs::create_dir_all(&dir)
+            .map_err(|error| format!("unable to create {}: {}", dir, error))?;
+        let path = self.path(registered, content);
+        let partial_path = format!("{}.part", path); // so a reader never sees half a file
+        fs::write(&partial_path, entries)
+            .and_then(|_| fs::rename(&partial_path, &path))
+            .map_err(|error| format!("unable to write {}: {}", path, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+    use super::super::day::Answer;
+    use super::super::registry::{Registered, Registry, DEFAULT_YEAR};
+    use super::super::remote::stub::scratch_dir;
+    use super::super::runner::PartRun;
+    use super::Cache;
+
+    fn day1() -> Registered {
+        Registry::new().get(DEFAULT_YEAR, 1).unwrap()
+    }
+
+    fn part_run(part: usize, answer: Answer) -> PartRun {
+        PartRun {
+            part,
+            answer,
+            time: Duration::ZERO,
+            cached: false
+        }
+    }
+
+    fn shown(answers: Vec<Option<Answer>>) -> Vec<Option<String>> {
+        answers.into_iter().map(|answer| answer.map(|answer| answer.to_string())).collect()
+    }
+
+    #[test]
+    fn stored_answers_load_again() {
+        let cache = Cache::new(&scratch_dir("cache-hit"));
+        cache.store(&day1(), "199\n200\n", &[part_run(1, Answer::Int(7)),
+                                            part_run(2, Answer::grid(["#.", ".#"].map(str::chars)))]).unwrap();
+        assert_eq!(shown(cache.load(&day1(), "199\n200\n", &[1, 2])),
+                   vec![Some(String::from("7")), Some(String::from("#.\n.#"))]);
+        assert_eq!(shown(cache.load(&day1(), "199\n200\n", &[2])), vec![Some(String::from("#.\n.#"))]);
+    }
+
+    #[test]
+    fn other_input_or_solver_version_misses() {
+        let cache = Cache::new(&scratch_dir("cache-miss"));
+        cache.store(&day1(), "199\n200\n", &[part_run(1, Answer::Int(1))]).unwrap();
+        assert_eq!(shown(cache.load(&day1(), "199\n201\n", &[1])), vec![None]);
+        let bumped = Registered {
+            version: day1().version + 1,
+            ..day1()
+        };
+        assert_eq!(shown(cache.load(&bumped, "199\n200\n", &[1])), vec![None]);
+    }
+
+    #[test]
+    fn parts_run_apart_are_merged() {
+        let cache = Cache::new(&scratch_dir("cache-merge"));
+        cache.store(&day1(), "199\n200\n", &[part_run(1, Answer::Int(1))]).unwrap();
+        assert_eq!(shown(cache.load(&day1(), "199\n200\n", &[1, 2])), vec![Some(String::from("1")), None]);
+        cache.store(&day1(), "199\n200\n", &[part_run(2, Answer::NotApplicable)]).unwrap();
+        assert_eq!(shown(cache.load(&day1(), "199\n200\n", &[1, 2])),
+                   vec![Some(String::from("1")), Some(String::from("n/a"))]);
+    }
+
+    #[test]
+    fn corrupt_file_is_ignored_and_replaced() {
+        let dir = scratch_dir("cache-corrupt");
+        let cache = Cache::new(&dir);
+        cache.store(&day1(), "199\n200\n", &[part_run(1, Answer::Int(1))]).unwrap();
+        let path = cache.path(&day1(), "199\n200\n");
+        fs::write(&path, "cached 3: 12\n|stray\n").unwrap();
+        assert_eq!(shown(cache.load(&day1(), "199\n200\n", &[1])), vec![None]);
+        cache.store(&day1(), "199\n200\n", &[part_run(1, Answer::Int(1))]).unwrap();
+        assert_eq!(shown(cache.load(&day1(), "199\n200\n", &[1])), vec![Some(String::from("1"))]);
+    }
+}
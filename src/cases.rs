@@ -0,0 +1,240 @@
+use std::fs;
+use std::io;
+use std::time::Duration;
+use itertools::Itertools;
+use super::day::Answer;
+use super::registry::Registered;
+use super::runner::{self, DayRecord, DayRun, PartRun};
+use super::verify::{self, AnswerKey};
+
+// dayNN/tests/<name>.txt is one sample case, its expected answers in answers.txt syntax keyed "part",
+// then a "===" line, then the input, eg
+//   part 1: 16
+//   ===
+//   8A004A801A8002F478
+const CASE_KEY: &str = "part";
+const CASE_SEPARATOR: &str = "===";
+
+pub struct Case {
+    pub name: String,
+    pub content: String,
+    pub expected: AnswerKey
+}
+
+pub fn cases_dir(root: &str, day_num: usize) -> String {
+    format!("{}/day{:02}/tests", root, day_num)
+}
+
+impl Case {
+    pub fn from_content(name: &str, file_content: &str) -> Result<Case, String> {
+        let mut header: Vec<&str> = Vec::new();
+        let mut lines = file_content.split_inclusive('\n');
+        loop {
+            match lines.next() {
+                None => return Err(format!("no \"{}\" line between the answers and the input", CASE_SEPARATOR)),
+                Some(line) if line.trim() == CASE_SEPARATOR => break,
+                Some(line) => header.push(line)
+            }
+        }
+        let expected = AnswerKey::from_content(&header.concat())?;
+        if runner::PARTS.iter().all(|part| expected.expected(CASE_KEY, *part).is_none()) {
+            return Err(format!("no \"{} <part>: <answer>\" lines before the \"{}\" line", CASE_KEY, CASE_SEPARATOR));
+        }
+        Ok(Case {
+            name: String::from(name),
+            content: lines.collect(),
+            expected
+        })
+    }
+
+    // only the parts the case has answers for are worth running
+    pub fn parts(&self, parts: &[usize]) -> Vec<usize> {
+        parts.iter()
+            .cloned()
+            .filter(|part| self.expected.expected(CASE_KEY, *part).is_some())
+            .collect()
+    }
+
+    // the parts run that did not give the expected answer, with what was expected
+    fn mismatches<'a>(&'a self, run: &'a DayRun) -> Vec<(&'a PartRun, &'a Answer)> {
+        run.parts.iter()
+            .filter_map(|part_run| self.expected.expected(CASE_KEY, part_run.part)
+                .filter(|expected| **expected != part_run.answer)
+                .map(|expected| (part_run, expected)))
+            .collect()
+    }
+}
+
+fn skipped_desc(parts: &[usize]) -> String {
+    format!("skipped, no answer for part {}", parts.iter().join(" or "))
+}
+
+// sorted by name, no tests directory is no cases
+pub fn load_cases(root: &str, day_num: usize) -> Result<Vec<Case>, String> {
+    let dir = cases_dir(root, day_num);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("unable to open cases directory {}: {}", dir, error))
+    };
+    let mut paths: Vec<_> = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()
+        .map_err(|error| format!("unable to list {}: {}", dir, error))?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+    paths.sort();
+    paths.iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            let file_content = fs::read_to_string(path)
+                .map_err(|error| format!("unable to open case {}: {}", path.display(), error))?;
+            Case::from_content(&name, &file_content)
+                .map_err(|desc| format!("{}: {}", path.display(), desc))
+        })
+        .collect()
+}
+
+// how checking a day's cases went
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CaseCounts {
+    pub cases: usize,
+    pub failures: usize,
+    pub skipped: usize
+}
+
+// prints pass, FAIL or skipped per case, a case with no answer for the parts asked for is skipped
+pub fn check_cases(registered: &Registered, root: &str, parts: &[usize], timeout: Option<Duration>) -> CaseCounts {
+    let day_num = registered.day;
+    let cases = match load_cases(root, day_num) {
+        Ok(cases) => cases,
+        Err(desc) => {
+            println!("day {:>2} cases: FAILED: {}", day_num, desc);
+            return CaseCounts { cases: 1, failures: 1, skipped: 0 };
+        }
+    };
+    let mut counts = CaseCounts { cases: cases.len(), ..CaseCounts::default() };
+    for case in cases.iter() {
+        let case_parts = case.parts(parts);
+        if case_parts.is_empty() {
+            counts.skipped += 1;
+            println!("day {:>2} case {}: {}", day_num, case.name, skipped_desc(parts));
+            continue;
+        }
+        let run = match runner::guarded_run(registered.maker, &case.content, &case_parts, timeout) {
+            Ok(run) => run,
+            Err(desc) => {
+                counts.failures += 1;
+                println!("day {:>2} case {}: FAIL: {}", day_num, case.name, desc);
+                continue;
+            }
+        };
+        let mismatches = case.mismatches(&run);
+        if mismatches.is_empty() {
+            println!("day {:>2} case {}: pass", day_num, case.name);
+        } else {
+            counts.failures += 1;
+            println!("day {:>2} case {}: FAIL", day_num, case.name);
+            for (part_run, expected) in mismatches {
+                println!("  part {}:", part_run.part);
+                verify::print_answer("- expected", expected);
+                verify::print_answer("+ actual", &part_run.answer);
+            }
+        }
+    }
+    counts
+}
+
+pub fn check_days(days: &[Registered], root: &str, parts: &[usize], timeout: Option<Duration>) -> bool {
+    let counts = days.iter()
+        .map(|registered| check_cases(registered, root, parts, timeout))
+        .fold(CaseCounts::default(), |total, day| CaseCounts {
+            cases: total.cases + day.cases,
+            failures: total.failures + day.failures,
+            skipped: total.skipped + day.skipped
+        });
+    if counts.skipped > 0 {
+        println!("{} cases, {} failures, {} skipped", counts.cases, counts.failures, counts.skipped);
+    } else if counts.cases > 0 {
+        println!("{} cases, {} failures", counts.cases, counts.failures);
+    }
+    counts.failures == 0
+}
+
+// the cases as records for json and csv, input "tests/<name>". a case whose answers don't match is an error
+// record saying which, one skipped for want of answers to the parts asked for is left out
+pub fn case_records(registered: Registered, root: &str, parts: &[usize], timeout: Option<Duration>) -> Vec<DayRecord> {
+    let record = |input: String, result| DayRecord {
+        year: registered.year,
+        day_num: registered.day,
+        input,
+        result
+    };
+    let cases = match load_cases(root, registered.day) {
+        Ok(cases) => cases,
+        Err(desc) => return vec![record(String::from("tests"), Err(desc))]
+    };
+    cases.iter()
+        .filter(|case| !case.parts(parts).is_empty())
+        .map(|case| {
+            let result = runner::guarded_run(registered.maker, &case.content, &case.parts(parts), timeout)
+                .and_then(|run| match case.mismatches(&run)[..] {
+                    [] => Ok(run),
+                    ref mismatches => Err(mismatches.iter()
+                        .map(|(part_run, expected)| format!("part {} expected {} but got {}",
+                                                            part_run.part, expected, part_run.answer))
+                        .join(", "))
+                });
+            record(format!("tests/{}", case.name), result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::super::registry::{Registry, DEFAULT_YEAR};
+    use super::super::remote::stub::scratch_dir;
+    use super::{case_records, cases_dir, check_cases, Case, CaseCounts, CASE_KEY};
+
+    #[test]
+    fn answers_then_input_after_the_separator() {
+        let case = Case::from_content("sum", "part 1: 4\n# a comment\npart 2: grid\n|#.\n|.#\n===\n199\n===\n200\n").unwrap();
+        assert_eq!(case.name, "sum");
+        // only the first separator counts, the rest is input as it was
+        assert_eq!(case.content, "199\n===\n200\n");
+        assert_eq!(case.expected.expected(CASE_KEY, 1).unwrap().to_string(), "4");
+        assert_eq!(case.expected.expected(CASE_KEY, 2).unwrap().to_string(), "#.\n.#");
+        assert_eq!(case.parts(&[1, 2]), vec![1, 2]);
+    }
+
+    #[test]
+    fn missing_separator_or_answers_is_refused() {
+        assert_eq!(Case::from_content("a", "part 1: 4\n199\n").err(),
+                   Some(String::from("no \"===\" line between the answers and the input")));
+        assert_eq!(Case::from_content("b", "# nothing yet\n===\n199\n").err(),
+                   Some(String::from("no \"part <part>: <answer>\" lines before the \"===\" line")));
+        assert!(Case::from_content("c", "part 3: 4\n===\n199\n").is_err());
+    }
+
+    #[test]
+    fn failing_cases_are_error_records_and_skipped_ones_left_out() {
+        let root = scratch_dir("cases");
+        let dir = cases_dir(&root, 1);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(format!("{}/a-pass.txt", dir), "part 1: 2\n===\n1\n2\n3\n").unwrap();
+        fs::write(format!("{}/b-fail.txt", dir), "part 1: 2\npart 2: 5\n===\n1\n2\n3\n").unwrap();
+        fs::write(format!("{}/c-part2-only.txt", dir), "part 2: 0\n===\n1\n2\n3\n").unwrap();
+        let day1 = Registry::new().get(DEFAULT_YEAR, 1).unwrap();
+
+        let records = case_records(day1, &root, &[1, 2], None);
+        let inputs: Vec<&str> = records.iter().map(|record| record.input.as_str()).collect();
+        assert_eq!(inputs, vec!["tests/a-pass", "tests/b-fail", "tests/c-part2-only"]);
+        assert!(records[0].result.is_ok());
+        assert_eq!(records[1].result.as_ref().err(), Some(&String::from("part 2 expected 5 but got 0")));
+
+        let records = case_records(day1, &root, &[1], None);
+        assert_eq!(records.len(), 2);
+        assert_eq!(check_cases(&day1, &root, &[1], None), CaseCounts { cases: 3, failures: 0, skipped: 1 });
+        assert_eq!(check_cases(&day1, &root, &[1, 2], None), CaseCounts { cases: 3, failures: 1, skipped: 0 });
+    }
+}
@@ -1,6 +1,226 @@
-pub trait Day {
-    fn part1(&mut self) -> isize;
-    fn part2(&mut self) -> isize;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// parts only read the parsed input, so either can run any number of times or both at once
+pub trait Day: Send + Sync {
+    fn part1(&self) -> Answer;
+    fn part2(&self) -> Answer;
+
+    // set-up too slow to count as parsing, run up front so it is timed on its own and can be cancelled.
+    // parts must not depend on it being called, a day that skips it does the set-up on first use
+    fn prepare(&self, _cancel: &CancelToken) {}
+
+    // days that can run long override these to check cancel now and then and give up once it fires,
+    // what they answer after that is thrown away
+    fn part1_until(&self, _cancel: &CancelToken) -> Answer {
+        self.part1()
+    }
+
+    fn part2_until(&self, _cancel: &CancelToken) -> Answer {
+        self.part2()
+    }
+}
+
+// cheap to copy into worker threads, it is only a deadline
+#[derive(Debug, Clone, Copy)]
+pub struct CancelToken {
+    deadline: Option<Instant>
 }
 
-pub type DayMaker = fn (content: &str) -> Result<Box<dyn Day>, &'static str>;
\ No newline at end of file
+impl CancelToken {
+    pub fn never() -> Self {
+        CancelToken {
+            deadline: None
+        }
+    }
+
+    pub fn after(timeout: Duration) -> Self {
+        CancelToken {
+            deadline: Some(Instant::now() + timeout)
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+pub type DayMaker = fn (content: &str) -> Result<Box<dyn Day>, ParseError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(day: usize, line: usize, column: usize, message: S) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            message: message.into()
+        }
+    }
+
+    // locate a fragment that was sliced out of content, lines and columns count from 1
+    pub fn at<S: Into<String>>(day: usize, content: &str, fragment: &str, message: S) -> Self {
+        let start = content.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| *offset <= content.len())
+            .unwrap_or(content.len());
+        let before = &content[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+        ParseError::new(day, line, column, message)
+    }
+
+    pub fn eof<S: Into<String>>(day: usize, content: &str, message: S) -> Self {
+        ParseError::at(day, content, &content[content.len()..], message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} line {} column {}: {}", self.day, self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(isize),
+    BigInt(i128),
+    Str(String),
+    Grid(Vec<String>),
+    NotApplicable
+}
+
+impl Answer {
+    pub fn grid<I, R>(rows: I) -> Answer
+        where I: IntoIterator<Item = R>,
+              R: IntoIterator<Item = char>
+    {
+        Answer::Grid(rows.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect())
+    }
+
+    // single line form, for tables and other places a grid won't fit
+    pub fn inline(&self) -> String {
+        match self {
+            Answer::Grid(rows) => format!("<{}x{} grid>",
+                                          rows.len(),
+                                          rows.iter().map(|row| row.chars().count()).max().unwrap_or(0)),
+            _ => self.to_string()
+        }
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(_))
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Int(num) => Some(*num as i128),
+            Answer::BigInt(num) => Some(*num),
+            _ => None
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Str(l), Answer::Str(r)) => l == r,
+            (Answer::Grid(l), Answer::Grid(r)) => l == r,
+            (Answer::NotApplicable, Answer::NotApplicable) => true,
+            _ => match (self.as_i128(), other.as_i128()) {
+                (Some(l), Some(r)) => l == r, // Int and BigInt are only storage
+                _ => false
+            }
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(num) => write!(f, "{}", num),
+            Answer::BigInt(num) => write!(f, "{}", num),
+            Answer::Str(word) => write!(f, "{}", word),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::NotApplicable => write!(f, "n/a")
+        }
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(num: isize) -> Self {
+        Answer::Int(num)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(num: usize) -> Self {
+        match isize::try_from(num) {
+            Ok(num) => Answer::Int(num),
+            Err(_) => Answer::BigInt(num as i128)
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(num: i128) -> Self {
+        match isize::try_from(num) {
+            Ok(num) => Answer::Int(num),
+            Err(_) => Answer::BigInt(num)
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(word: String) -> Self {
+        Answer::Str(word)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(word: &str) -> Self {
+        Answer::Str(String::from(word))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+    use super::super::registry::{Registry, DEFAULT_YEAR};
+    use super::super::runner;
+
+    // parts only take &self, so asking again or asking from two threads at once gives the same answers
+    #[test]
+    fn parts_are_repeatable_and_thread_safe() {
+        for registered in Registry::new().year(DEFAULT_YEAR) {
+            let path = runner::content_path(".", registered.day, true);
+            let content = fs::read_to_string(&path).unwrap();
+            let day = (registered.maker)(&content).unwrap();
+
+            let first = (day.part1(), day.part2());
+            let again = (day.part1(), day.part2());
+            let threaded = thread::scope(|scope| {
+                let part1 = scope.spawn(|| day.part1());
+                let part2 = scope.spawn(|| day.part2());
+                (part1.join().unwrap(), part2.join().unwrap())
+            });
+            assert_eq!(first, again, "day {} answered differently the second time", registered.day);
+            assert_eq!(first, threaded, "day {} answered differently on separate threads", registered.day);
+        }
+    }
+}
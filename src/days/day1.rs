@@ -1,5 +1,7 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 
+const DAY: usize = 1;
 const WIN_SZ: usize = 3;
 
 pub struct Day1 {
@@ -7,19 +9,20 @@ pub struct Day1 {
 }
 
 impl Day1 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let depths: Vec<isize> = parser.lines()
+            .map(|line| parser.num(line))
+            .collect::<Result<_, _>>()?;
         Ok(Box::new(Day1 {
-            depths: content
-                .split_whitespace()
-                .map(|s| s.parse::<isize>().expect("not a num"))
-                .collect()
+            depths
         }))
     }
 }
 
 impl Day for Day1 {
 
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let mut incrs = 0;
         let mut last_depth = -1;
         
@@ -30,10 +33,13 @@ impl Day for Day1 {
             last_depth = *depth;
         }
 
-        incrs
+        Answer::Int(incrs)
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
+        if self.depths.len() < WIN_SZ {
+            return Answer::NotApplicable; // not one full window
+        }
         let mut incrs = 0;
         let mut last_window: isize = -1;
 
@@ -45,6 +51,20 @@ impl Day for Day1 {
             last_window = curr_window;
         }
 
-        incrs
+        Answer::Int(incrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::day::Answer;
+    use super::Day1;
+
+    #[test]
+    fn too_few_depths_for_a_window() {
+        let day = Day1::from_content("199\n200\n").unwrap();
+        assert!(matches!(day.part1(), Answer::Int(1)));
+        assert!(matches!(day.part2(), Answer::NotApplicable));
+        assert!(matches!(Day1::from_content("199\n200\n208\n").unwrap().part2(), Answer::Int(0)));
     }
-}
\ No newline at end of file
+}
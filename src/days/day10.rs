@@ -1,22 +1,37 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use std::collections::HashMap;
 use bimap::BiHashMap;
 use itertools::Itertools;
 
+const DAY: usize = 10;
+
 pub struct Day10 {
     braces: Vec<Vec<char>>
 }
 
 impl Day10 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let braces: Vec<Vec<char>> = parser.lines()
+            .map(|line| line.char_indices()
+                .map(|(i, c)| match c {
+                    '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' => Ok(c),
+                    _ => Err(parser.error(&line[i..], format!("not a brace: {}", c)))
+                })
+                .collect())
+            .collect::<Result<_, _>>()?;
+        if braces.is_empty() {
+            return Err(parser.eof("no lines"));
+        }
         Ok(Box::new(Day10 {
-            braces: content.lines().map(|line| line.chars().collect()).collect()
+            braces
         }))
     }
 }
 
 impl Day for Day10 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let close: HashMap<char, char> = HashMap::from([(')', '('), (']', '['), ('}', '{'), ('>', '<')]);
         let points: HashMap<char, usize> = HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]);
 
@@ -24,12 +39,12 @@ impl Day for Day10 {
         for entry in &self.braces {
             let mut stack: Vec<char> = Vec::new();
             for c in entry {
-                if close.contains_key(&c) {
+                if close.contains_key(c) {
                     match stack.pop() {
                         None => {break}
                         Some(peer) => {
-                            if peer != *close.get(&c).unwrap() {
-                                sum += *points.get(&c).unwrap() as isize;
+                            if peer != *close.get(c).unwrap() {
+                                sum += *points.get(c).unwrap() as isize;
                                 break;
                             }
                         }
@@ -39,10 +54,10 @@ impl Day for Day10 {
                 }
             }
         }
-        sum
+        Answer::Int(sum)
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let peers: BiHashMap<char, char> = BiHashMap::from_iter(vec![(')', '('), (']', '['), ('}', '{'), ('>', '<')]);
         let point_vals: HashMap<char, isize> = HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)]);
 
@@ -51,7 +66,7 @@ impl Day for Day10 {
             let mut incomplete = true;
             let mut stack: Vec<char> = Vec::new();
             for c in entry {
-                match peers.get_by_left(&c) {
+                match peers.get_by_left(c) {
                     None => {
                         // must be an open
                         stack.push(*c);
@@ -84,6 +99,9 @@ impl Day for Day10 {
             }
         }
         let entry_points: Vec<isize> = entry_points.into_iter().sorted().collect();
-        entry_points[entry_points.len() / 2]
+        match entry_points.get(entry_points.len() / 2) {
+            Some(points) => Answer::Int(*points),
+            None => Answer::NotApplicable // no line was incomplete
+        }
     }
 }
\ No newline at end of file
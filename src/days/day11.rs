@@ -1,138 +1,86 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::grid::{Grid, Pos};
+use super::super::parse::Parser;
+
+const DAY: usize = 11;
 
 pub struct Day11 {
     octopuses: OctopusGrid
 }
 
 impl Day11 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
         Ok(Box::new(Day11 {
-            octopuses: OctopusGrid::from_string(content)
+            octopuses: OctopusGrid::from_string(content)?
         }))
     }
 }
 
 impl Day for Day11 {
-    fn part1(&mut self) -> isize {
-        self.octopuses.clone().count_flashes(100)
-    }
-
-    fn part2(&mut self) -> isize {
-        self.octopuses.clone().first_sync()
+    fn part1(&self) -> Answer {
+        Answer::Int(self.octopuses.clone().count_flashes(100))
     }
-}
-
-#[derive(Debug)]
-struct Pos {
-    row: isize,
-    col: isize
-}
 
-impl Pos {
-    fn from_idxs(row: isize, col: isize) -> Pos {
-        Pos {row, col}
-    }
-    fn delta(&self, row_d: isize, col_d: isize) -> Pos {
-        Pos {
-            row: self.row + row_d,
-            col: self.col + col_d
+    fn part2(&self) -> Answer {
+        match self.octopuses.clone().first_sync() {
+            Some(cycle) => Answer::Int(cycle),
+            None => Answer::NotApplicable
         }
     }
-    fn row(&self) -> usize {
-        self.row as usize
-    }
-    fn col(&self) -> usize {
-        self.col as usize
-    }
 }
 
 #[derive(Clone)]
 struct OctopusGrid {
-    energies: Vec<Vec<usize>>
+    energies: Grid<usize>
 }
 
 impl OctopusGrid {
-    fn from_string(content: &str) -> OctopusGrid {
-        let energies: Vec<Vec<usize>> = content.lines()
-            .map(|line| line.chars()
-                .map(|c| c as usize - '0' as usize)
-                .collect())
-            .collect();
-        assert_eq!(energies.iter().map(Vec::len).min(), energies.iter().map(Vec::len).max());
-        OctopusGrid {energies}
-    }
-
-    fn all_pos(&self) -> Vec<Pos> {
-        (0isize..self.energies.len() as isize).into_iter().map(|row| {
-            (0isize..self.energies[0].len() as isize).into_iter().map(|col| {
-                Pos::from_idxs(row, col)
-            }).collect::<Vec<Pos>>()
-        }).flatten().collect()
-    }
-
-    fn valid_pos(&self, pos: &Pos) -> bool {
-        pos.row < (self.energies.len() as isize)
-            && pos.col < (self.energies[0].len() as isize)
-            && pos.row > -1
-            && pos.col > -1
+    fn from_string(content: &str) -> Result<OctopusGrid, ParseError> {
+        Ok(OctopusGrid {
+            energies: Parser::new(DAY, content).digit_grid()?
+        })
     }
 
     fn incr_all(&mut self) {
-        self.all_pos().into_iter().for_each(|pos| self.incr(&pos));
+        let positions: Vec<Pos> = self.energies.positions().collect();
+        positions.into_iter().for_each(|pos| self.incr(pos));
     }
 
-    fn incr(&mut self, pos: &Pos) {
-        self.energies[pos.row()][pos.col()] += 1;
-        if self.energies[pos.row()][pos.col()] == 10 {
-            self.adjacent(pos).into_iter()
-                .for_each(|neighbor| self.incr(&neighbor));
+    fn incr(&mut self, pos: Pos) {
+        self.energies[pos] += 1;
+        if self.energies[pos] == 10 {
+            let neighbors: Vec<Pos> = self.energies.neighbors8(pos).collect();
+            neighbors.into_iter()
+                .for_each(|neighbor| self.incr(neighbor));
         }
     }
 
     fn prune_all(&mut self) -> isize {
-        self.all_pos().into_iter().fold(0isize, |flashes, pos| {
-            flashes + self.prune(&pos)
+        self.energies.values_mut().fold(0isize, |flashes, energy| {
+            if *energy > 9 {
+                *energy = 0;
+                flashes + 1
+            } else {
+                flashes
+            }
         })
     }
 
-    fn prune(&mut self, pos: &Pos) -> isize {
-        if self.energies[pos.row()][pos.col()] > 9 {
-            self.energies[pos.row()][pos.col()] = 0;
-            1
-        } else {
-            0
-        }
-    }
-
-    fn adjacent(&self, target: &Pos) -> Vec<Pos> {
-        [
-            target.delta(-1, 0), // up
-            target.delta(-1, 1), // upright
-            target.delta(0, 1), // right
-            target.delta(1, 1), // downright
-            target.delta(1, 0), // down
-            target.delta(1, -1), // downleft
-            target.delta(0, -1), // left
-            target.delta(-1, -1) // upleft
-        ].into_iter()
-            .filter(|pos| self.valid_pos(pos))
-            .collect()
-    }
-
     fn count_flashes(&mut self, cycles: usize) -> isize {
-        (0..cycles).into_iter().fold(0isize, |flashes, _cycle| {
+        (0..cycles).fold(0isize, |flashes, _cycle| {
             self.incr_all();
             flashes + self.prune_all()
         })
     }
 
-    fn first_sync(&mut self) -> isize {
+    // None when they are still out of step after 1000 cycles
+    fn first_sync(&mut self) -> Option<isize> {
         for cycle in 1..1000 {
             self.incr_all();
-            if self.prune_all() == (self.energies.len() * self.energies[0].len()) as isize {
-                return cycle
+            if self.prune_all() == self.energies.len() as isize {
+                return Some(cycle)
             }
         }
-        0
+        None
     }
-}
\ No newline at end of file
+}
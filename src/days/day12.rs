@@ -1,39 +1,48 @@
-use super::super::day::Day;
-use itertools::Itertools;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use petgraph::prelude::*;
 use std::collections::HashMap;
 
-const START_ID: &'static str = "start";
-const END_ID: &'static str = "end";
+const DAY: usize = 12;
+const START_ID: &str = "start";
+const END_ID: &str = "end";
 
 pub struct Day12 {
-    content: String
+    edges: Vec<(String, String)>
 }
 
 impl Day12 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let mut edges = Vec::new();
+        for line in parser.lines() {
+            let (from, to) = parser.pair(line, "-", "<cave>-<cave>")?;
+            if let Some(cave) = [from, to].into_iter().find(|cave| !is_cave(cave)) {
+                return Err(parser.error(cave, format!("not a cave: {}", cave)));
+            }
+            edges.push((String::from(from), String::from(to)));
+        }
         Ok(Box::new(Day12 {
-            content: String::from(content)
+            edges
         }))
     }
 
     fn build_graph(&self) -> UnGraphMap<&str, ()> {
-        self.content.split_whitespace()
-            .flat_map(|word| word.split('-'))
-            .tuples::<(_, _)>()
+        self.edges.iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
             .collect()
     }
 }
 
 impl Day for Day12 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let graph = self.build_graph();
-        all_paths_start(&graph, is_visitable_small_caves_once) as isize
+        all_paths_start(&graph, is_visitable_small_caves_once).into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let graph = self.build_graph();
-        all_paths_start(&graph, is_visitable_small_caves_once_except_one_small_cave_twice) as isize
+        all_paths_start(&graph, is_visitable_small_caves_once_except_one_small_cave_twice).into()
     }
 }
 
@@ -56,7 +65,7 @@ fn all_paths<'a, 'b: 'a>(
         hits + if next_node == END_ID {
             1
         } else if is_visitable(next_node, &visits) {
-            all_paths(&graph, next_node, visits.clone(), is_visitable)
+            all_paths(graph, next_node, visits.clone(), is_visitable)
         } else {
             0
         }
@@ -68,10 +77,8 @@ fn is_visitable_small_caves_once(id: &str, visits: &HashMap<&str, usize>) -> boo
         false
     } else if is_big_cave(id) {
         true
-    } else if is_small_cave(id) && *visits.get(id).unwrap_or(&0) < 1 {
-        true
     } else {
-        false
+        is_small_cave(id) && *visits.get(id).unwrap_or(&0) < 1
     }
 }
 
@@ -82,27 +89,25 @@ fn is_visitable_small_caves_once_except_one_small_cave_twice(id: &str, visits: &
         true
     } else if is_small_cave(id) {
         let node_visits = *visits.get(id).unwrap_or(&0);
-        if node_visits < 1 {
-            true
-        } else if node_visits == 1 && visits.iter()
+        node_visits < 1 || (node_visits == 1 && visits.iter()
                 .filter(|(check_id, _)| is_small_cave(check_id) && *id != ***check_id)
                 .map(|(_, val)| val)
-                .all(|v| *v < 2) {
-            true
-        } else {
-            false
-        }
+                .all(|v| *v < 2))
     } else {
         false
     }
 }
 
+fn is_cave(id: &str) -> bool {
+    !id.is_empty() && (id.chars().all(|c| c.is_ascii_lowercase()) || id.chars().all(|c| c.is_ascii_uppercase()))
+}
+
 fn is_small_cave(id: &str) -> bool {
     let first_char = id.chars().next().unwrap();
-    first_char >= 'a' && first_char <= 'z'
+    first_char.is_ascii_lowercase()
 }
 
 fn is_big_cave(id: &str) -> bool {
     let first_char = id.chars().next().unwrap();
-    first_char >= 'A' && first_char <= 'Z'
+    first_char.is_ascii_uppercase()
 }
\ No newline at end of file
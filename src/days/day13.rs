@@ -1,49 +1,53 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use itertools::Itertools;
 use array2d::Array2D;
 
+const DAY: usize = 13;
+
 pub struct Day13 {
     points: Vec<Pos>,
     folds: Vec<Fold>,
 }
 
 impl Day13 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let mut today = Day13 {
-            points: Vec::new(),
-            folds: Vec::new()
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let blocks = parser.blocks();
+        let (points_block, folds_block) = match blocks[..] {
+            [points_block, folds_block] => (points_block, folds_block),
+            [] => return Err(parser.eof("no points")),
+            [_] => return Err(parser.eof("no folds")),
+            [_, _, extra, ..] => return Err(parser.error(extra, "expected points, a blank line, then folds"))
         };
-        for word in content.split_whitespace() {
-            if word.contains(',') {
-                today.points.push(Pos::from_str(word).unwrap());
-            } else if word.contains('=') {
-                today.folds.push(Fold::from_str(word).unwrap())
-            }
-        }
-        Ok(Box::new(today))
+        Ok(Box::new(Day13 {
+            points: points_block.lines()
+                .map(|line| Pos::from_str(&parser, line))
+                .collect::<Result<_, _>>()?,
+            folds: folds_block.lines()
+                .map(|line| Fold::from_str(&parser, parser.prefixed(line, "fold along")?))
+                .collect::<Result<_, _>>()?
+        }))
     }
 }
 
 impl Day for Day13 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let folded: Vec<Pos> = self.folds[0].fold_points(self.points.clone());
-        folded.len() as isize
+        folded.len().into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let folded: Vec<Pos> = self.folds.iter()
             .fold(self.points.clone(),
                   |points, fold_instr| fold_instr.fold_points(points));
         let max_x = folded.iter().map(|pos| pos.x).max().unwrap();
         let max_y = folded.iter().map(|pos| pos.y).max().unwrap();
-        let mut grid: Array2D<char> = Array2D::filled_with(' ', max_y + 1, max_x + 1);
+        let mut grid: Array2D<char> = Array2D::filled_with('.', max_y + 1, max_x + 1);
         for pos in folded {
             grid.set(pos.y, pos.x, '#').unwrap();
         }
-        grid.rows_iter().for_each(|mut cell_iter| {
-            eprintln!("{}", cell_iter.join(""));
-        });
-        0
+        Answer::grid(grid.rows_iter().map(|cell_iter| cell_iter.cloned()))
     }
 }
 
@@ -59,18 +63,16 @@ struct Fold {
 }
 
 impl Fold {
-    fn from_str(word: &str) -> Option<Fold> {
-        word.split('=')
-            .tuples()
-            .map(|(s_dir, s_loc)| Fold {
-                dir: match s_dir {
-                    "x" => FoldDir::Col,
-                    "y" => FoldDir::Row,
-                    _ => panic!("unsupported fold direction")
-                },
-                location: s_loc.parse().unwrap()
-            })
-            .next()
+    fn from_str<'a>(parser: &Parser<'a>, word: &'a str) -> Result<Fold, ParseError> {
+        let (s_dir, s_loc) = parser.pair(word, "=", "<x|y>=<location>")?;
+        Ok(Fold {
+            dir: match s_dir {
+                "x" => FoldDir::Col,
+                "y" => FoldDir::Row,
+                _ => return Err(parser.error(s_dir, format!("unsupported fold direction {}", s_dir)))
+            },
+            location: parser.num(s_loc)?
+        })
     }
 
     fn fold_point(&self, pos: Pos) -> Pos {
@@ -102,11 +104,39 @@ struct Pos {
 }
 
 impl Pos {
-    fn from_str(word: &str) -> Option<Pos> {
-        word.split(',')
-            .flat_map(|n| n.parse().ok())
-            .tuples()
-            .map(|(x, y)| Pos { x, y })
-            .next()
+    fn from_str<'a>(parser: &Parser<'a>, word: &'a str) -> Result<Pos, ParseError> {
+        let (x, y) = parser.pair(word, ",", "x,y")?;
+        Ok(Pos {
+            x: parser.num(x)?,
+            y: parser.num(y)?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day13;
+
+    fn error_at(content: &str) -> (usize, usize, String) {
+        let error = Day13::from_content(content).err().unwrap();
+        (error.line, error.column, error.message)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn bad_folds() {
+        assert_eq!(error_at("6,10\n0,14\n\nfold along z=7\n"), (4, 12, String::from("unsupported fold direction z")));
+        assert_eq!(error_at("6,10\n\nfold along y=x\n"), (3, 14, String::from("not a num: x")));
+        assert_eq!(error_at("6,10\n\nfold at y=7\n"), (3, 1, String::from("expected \"fold along\"")));
+    }
+
+    #[test]
+    fn bad_points() {
+        assert_eq!(error_at("6,10\n6;10\n\nfold along y=7\n"), (2, 1, String::from("expected \"x,y\"")));
+    }
+
+    #[test]
+    fn missing_blocks() {
+        assert_eq!(error_at("6,10\n"), (2, 1, String::from("no folds")));
+        assert_eq!(error_at("6,10\n\nfold along y=7\n\n1,1\n"), (5, 1, String::from("expected points, a blank line, then folds")));
+    }
+}
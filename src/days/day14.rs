@@ -1,7 +1,10 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use std::collections::HashMap;
 use itertools::Itertools;
 
+const DAY: usize = 14;
+
 type Pair = (char, char);
 
 pub struct Day14 {
@@ -10,15 +13,24 @@ pub struct Day14 {
 }
 
 impl Day14 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let mut words = content.split_whitespace();
-        let base = PolymerHist::from_str(words.next().unwrap());
-        let insertions: HashMap<Pair, char> = words.tuples()
-            .map(|(pair_s, _, ins_s)| (
-                (pair_s.chars().tuples::<(_, _)>().next().unwrap()),
-                ins_s.chars().next().unwrap()
-            ))
-            .collect();
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let mut lines = parser.lines();
+        let template = lines.next()
+            .ok_or_else(|| parser.eof("no polymer template"))?;
+        if template.len() < 2 || !template.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(parser.error(template, "polymer template is two or more elements"));
+        }
+        let base = PolymerHist::from_str(template);
+        let insertions: HashMap<Pair, char> = lines
+            .map(|line| match parser.pair(line, "->", "<pair> -> <element>")? {
+                (pair_s, ins_s) if pair_s.len() == 2 && ins_s.len() == 1 => Ok((
+                    (pair_s.chars().tuples::<(_, _)>().next().unwrap()),
+                    ins_s.chars().next().unwrap()
+                )),
+                _ => Err(parser.error(line, "expected \"<pair> -> <element>\""))
+            })
+            .collect::<Result<_, _>>()?;
         Ok(Box::new(Day14 {
             base,
             insertions
@@ -27,16 +39,18 @@ impl Day14 {
 }
 
 impl Day for Day14 {
-    fn part1(&mut self) -> isize {
-        (0..10).into_iter()
+    fn part1(&self) -> Answer {
+        (0..10)
             .fold(self.base.clone(), |hist, _| hist.mutate(&self.insertions))
-            .min_max_count_diff() as isize
+            .min_max_count_diff()
+            .into()
     }
 
-    fn part2(&mut self) -> isize {
-        (0..40).into_iter()
+    fn part2(&self) -> Answer {
+        (0..40)
             .fold(self.base.clone(), |hist, _| hist.mutate(&self.insertions))
-            .min_max_count_diff() as isize
+            .min_max_count_diff()
+            .into()
     }
 }
 
@@ -63,7 +77,7 @@ impl PolymerHist {
     fn mutate(&self, insertions: &HashMap<Pair, char>) -> PolymerHist {
         let mut hist: HashMap<Pair, usize> = HashMap::new();
         for (pair, count) in self.hist.iter() {
-            match insertions.get(&pair) {
+            match insertions.get(pair) {
                 None => {
                     *hist.entry(*pair).or_insert(0) += count;
                 }
@@ -1,53 +1,81 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use bitvec::prelude::*;
 
+const DAY: usize = 16;
+const HEX_BITS: usize = 4;
+
 type BitWord = u8;
-type BitOwner = BitVec<Msb0, BitWord>;
-type Bits = BitSlice<Msb0, BitWord>;
+type BitOwner = BitVec<BitWord, Msb0>;
+type Bits = BitSlice<BitWord, Msb0>;
 
 pub struct Day16 {
-    bits: BitOwner
+    packet: Packet
 }
 
 impl Day16 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let hex_str = content.trim();
+        let bits: BitOwner = match hex::decode(hex_str) {
+            Ok(bytes) => bytes.into_iter().collect(),
+            Err(hex::FromHexError::InvalidHexCharacter { c, index }) => {
+                return Err(parser.error(&hex_str[index..], format!("not a hex digit: {}", c)));
+            }
+            Err(error) => {
+                return Err(parser.error(hex_str, error.to_string()));
+            }
+        };
+        let mut scanner = BitSliceScanner::from_slice(bits.as_bitslice(), 0);
+        let packet = Packet::from_bits(&mut scanner)
+            .map_err(|(bit_pos, message)| {
+                let hex_pos = (bit_pos / HEX_BITS).min(hex_str.len());
+                parser.error(&hex_str[hex_pos..], message)
+            })?;
         Ok(Box::new(Day16 {
-            bits: hex::decode(content.trim()).unwrap().into_iter().collect()
+            packet
         }))
     }
 }
 
+// bit position and description of a malformed packet
+type PacketError = (usize, String);
+
 impl Day for Day16 {
-    fn part1(&mut self) -> isize {
-        let mut bits = BitSliceScanner::from_slice(self.bits.as_bitslice());
-        let packet = Packet::from_bits(&mut bits);
-        packet.add_versions() as isize
+    fn part1(&self) -> Answer {
+        self.packet.add_versions().into()
     }
 
-    fn part2(&mut self) -> isize {
-        let mut bits = BitSliceScanner::from_slice(self.bits.as_bitslice());
-        let packet = Packet::from_bits(&mut bits);
-        packet.execute()
+    fn part2(&self) -> Answer {
+        Answer::Int(self.packet.execute())
     }
 }
 
 struct BitSliceScanner<'a> {
     bits: &'a Bits,
+    base_pos: usize,
     curr_pos: usize
 }
 
 impl<'a> BitSliceScanner<'a> {
-    fn from_slice(bits: &Bits) -> BitSliceScanner {
+    fn from_slice(bits: &'a Bits, base_pos: usize) -> BitSliceScanner<'a> {
         BitSliceScanner {
             bits,
+            base_pos,
             curr_pos: 0
         }
     }
-    fn take_slice(&mut self, len: usize) -> &Bits {
+    fn take_slice(&mut self, len: usize) -> Result<&'a Bits, PacketError> {
         let start = self.curr_pos;
         let end = start + len;
+        if end > self.bits.len() {
+            return Err((self.pos(), format!("packet truncated, wanted {} more bits", end - self.bits.len())));
+        }
         self.curr_pos = end;
-        &self.bits[start..end]
+        Ok(&self.bits[start..end])
+    }
+    fn pos(&self) -> usize {
+        self.base_pos + self.curr_pos
     }
     fn has_more(&self) -> bool {
         self.curr_pos < self.bits.len()
@@ -89,11 +117,11 @@ struct PacketHeader {
 }
 
 impl PacketHeader {
-    fn from_bits(bits: &mut BitSliceScanner) -> PacketHeader {
-        PacketHeader {
-            version: bits.take_slice(3).load_be(),
-            packet_type: bits.take_slice(3).load_be::<usize>().into()
-        }
+    fn from_bits(bits: &mut BitSliceScanner) -> Result<PacketHeader, PacketError> {
+        Ok(PacketHeader {
+            version: bits.take_slice(3)?.load_be(),
+            packet_type: bits.take_slice(3)?.load_be::<usize>().into()
+        })
     }
 }
 
@@ -105,39 +133,47 @@ struct Packet {
 }
 
 impl Packet {
-    fn from_bits(bits: &mut BitSliceScanner) -> Packet {
-        let header = PacketHeader::from_bits(bits);
+    fn from_bits(bits: &mut BitSliceScanner) -> Result<Packet, PacketError> {
+        let start_pos = bits.pos();
+        let header = PacketHeader::from_bits(bits)?;
         let mut literal = None;
         let mut children: Vec<Packet> = Vec::new();
         match header.packet_type {
             PacketType::Literal => {
-                literal = Some(take_literal(bits));
+                literal = Some(take_literal(bits)?);
             }
             _ => {
-                if *bits.take_slice(1).first().unwrap() {
-                    let num_packets: usize = bits.take_slice(11).load_be();
+                if *bits.take_slice(1)?.first().unwrap() {
+                    let num_packets: usize = bits.take_slice(11)?.load_be();
                     for _ in 0..num_packets {
-                        children.push(Packet::from_bits(bits))
+                        children.push(Packet::from_bits(bits)?)
                     }
                 } else {
-                    let packets_size: usize = bits.take_slice(15).load_be();
-                    let mut next_bits = BitSliceScanner::from_slice(bits.take_slice(packets_size));
+                    let packets_size: usize = bits.take_slice(15)?.load_be();
+                    let sub_pos = bits.pos();
+                    let mut next_bits = BitSliceScanner::from_slice(bits.take_slice(packets_size)?, sub_pos);
                     while next_bits.has_more() {
-                        children.push(Packet::from_bits(&mut next_bits));
+                        children.push(Packet::from_bits(&mut next_bits)?);
                     }
                 }
+                if children.is_empty() {
+                    return Err((start_pos, String::from("operator packet without sub-packets")));
+                }
+                if [PacketType::Gt, PacketType::Lt, PacketType::Eq].contains(&header.packet_type) && children.len() != 2 {
+                    return Err((start_pos, format!("{:?} packet needs 2 sub-packets", header.packet_type)));
+                }
             }
         }
 
-        Packet {
+        Ok(Packet {
             header,
             literal,
             children
-        }
+        })
     }
 
     fn add_versions(&self) -> usize {
-        self.children.iter().fold(self.header.version as usize,
+        self.children.iter().fold(self.header.version,
           |sum, packet| sum + packet.add_versions())
     }
 
@@ -181,14 +217,39 @@ impl Packet {
     }
 }
 
-fn take_literal(bits: &mut BitSliceScanner) -> isize {
+fn take_literal(bits: &mut BitSliceScanner) -> Result<isize, PacketError> {
+    let start_pos = bits.pos();
     let mut lit_bits = BitOwner::new();
     loop {
-        let chunk = bits.take_slice(5);
+        let chunk = bits.take_slice(5)?;
         let cont = *chunk.first().unwrap();
         lit_bits.extend_from_bitslice(&chunk[1..]);
         if !cont {break}
     }
-    lit_bits.load_be::<usize>() as isize
+    if lit_bits.len() > usize::BITS as usize {
+        return Err((start_pos, format!("literal wider than {} bits", usize::BITS)));
+    }
+    Ok(lit_bits.load_be::<usize>() as isize)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::Day16;
+
+    fn error_at(content: &str) -> (usize, usize, String) {
+        let error = Day16::from_content(content).err().unwrap();
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn not_hex() {
+        assert_eq!(error_at("D2FE2G\n"), (1, 6, String::from("not a hex digit: G")));
+    }
+
+    #[test]
+    fn truncated_packet() {
+        // an operator that says 27 bits of packets follow, then ends two bits later
+        assert_eq!(error_at("38006F"), (1, 6, String::from("packet truncated, wanted 25 more bits")));
+    }
+}
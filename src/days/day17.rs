@@ -1,6 +1,9 @@
 use std::cmp;
 use std::collections::HashSet;
-use super::super::day::Day;
+use super::super::day::{Answer, CancelToken, Day, ParseError};
+use super::super::parse::Parser;
+
+const DAY: usize = 17;
 
 #[derive(Debug)]
 pub struct Day17 {
@@ -11,15 +14,23 @@ pub struct Day17 {
 }
 
 impl Day17 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let words: Vec<&str> = content.trim().split([' ', '=', ':', '.', ','])
-            .filter(|word| word.len() > 0)
-            .collect();
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let ranges = parser.prefixed(content, "target area:")?;
+        let (x_range, y_range) = parser.pair(ranges, ",", "x=<min>..<max>, y=<min>..<max>")?;
+        let (x_min, x_max) = parser.range(parser.key_value(x_range, "x")?)?;
+        let (y_min, y_max) = parser.range(parser.key_value(y_range, "y")?)?;
+        if x_min < 1 {
+            return Err(parser.error(x_range, "target must be right of the launcher"));
+        }
+        if y_max > -1 {
+            return Err(parser.error(y_range, "target must be below the launcher"));
+        }
         Ok(Box::new(Day17 {
-            x_min: words[3].parse().unwrap(),
-            x_max: words[4].parse().unwrap(),
-            y_min: words[6].parse().unwrap(),
-            y_max: words[7].parse().unwrap(),
+            x_min,
+            x_max,
+            y_min,
+            y_max,
         }))
     }
 
@@ -60,13 +71,24 @@ impl Day17 {
 }
 
 impl Day for Day17 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
+        self.part1_until(&CancelToken::never())
+    }
+
+    fn part2(&self) -> Answer {
+        self.part2_until(&CancelToken::never())
+    }
+
+    fn part1_until(&self, cancel: &CancelToken) -> Answer {
         let mut y_epoch = 0isize;
         let xv_min = 1isize; // really slow
         let xv_max = self.x_max; // immediate overshoot
         let yv_min = self.y_min; // immediate overshoot
         let yv_max = 1000; // math
         for xv in xv_min..=xv_max {
+            if cancel.is_cancelled() {
+                break;
+            }
             for yv in yv_min..=yv_max {
                 match self.launch(xv, yv) {
                     None => {}
@@ -76,16 +98,19 @@ impl Day for Day17 {
                 }
             }
         }
-        y_epoch
+        Answer::Int(y_epoch)
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2_until(&self, cancel: &CancelToken) -> Answer {
         let mut success: HashSet<(isize, isize)> = HashSet::new();
         let xv_min = 1isize; // really slow
         let xv_max = self.x_max; // immediate overshoot
         let yv_min = self.y_min; // immediate overshoot
         let yv_max = 1000; // math
         for xv in xv_min..=xv_max {
+            if cancel.is_cancelled() {
+                break;
+            }
             for yv in yv_min..=yv_max {
                 match self.launch(xv, yv) {
                     None => {}
@@ -95,6 +120,6 @@ impl Day for Day17 {
                 }
             }
         }
-        success.len() as isize
+        success.len().into()
     }
 }
\ No newline at end of file
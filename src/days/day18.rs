@@ -1,43 +1,60 @@
-use super::super::day::Day;
-use std::str::Chars;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
+use std::iter::Peekable;
+use std::str::CharIndices;
 use itertools::Itertools;
 use std::fmt::Formatter;
 use std::{cmp, fmt};
 
+const DAY: usize = 18;
+
 pub struct Day18 {
     numbers: Vec<SnailFishNumber>
 }
 
 impl Day18 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let numbers: Vec<SnailFishNumber> = parser.lines()
+            .map(|line| {
+                let mut iter = line.char_indices().peekable();
+                let number = SnailFishNumber::from_chars(&mut iter)
+                    .map_err(|(i, message)| parser.error(&line[i.min(line.len())..], message))?;
+                match iter.peek() {
+                    None => Ok(number),
+                    Some((i, c)) => Err(parser.error(&line[*i..], format!("trailing character {}", c)))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        if numbers.len() < 2 {
+            return Err(parser.eof("need at least two snailfish numbers"));
+        }
         Ok(Box::new(Day18 {
-            numbers: content.lines()
-                .map(|line| SnailFishNumber::from_chars(&mut line.chars()))
-                .collect()
+            numbers
         }))
     }
 }
 
 impl Day for Day18 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let mut numbers = self.numbers.clone();
         numbers.reverse();
         let mut sum = numbers.pop().unwrap();
         sum.reduce();
-        while numbers.len() > 0 {
+        while !numbers.is_empty() {
             sum = sum.add(numbers.pop().unwrap());
             sum.reduce();
         }
-        sum.magnitude()
+        Answer::Int(sum.magnitude())
     }
 
-    fn part2(&mut self) -> isize {
-        self.numbers.iter().permutations(2).fold(0isize, |max, pair| {
+    fn part2(&self) -> Answer {
+        Answer::Int(self.numbers.iter().permutations(2).fold(0isize, |max, pair| {
             let mut sum = pair[0].add_refs(pair[1]);
             sum.reduce();
             let mag = sum.magnitude();
             cmp::max(max, mag)
-        })
+        }))
     }
 }
 
@@ -55,56 +72,58 @@ enum Side {
 
 impl SnailFishNumber {
 
-    fn from_chars(iter: &mut Chars) -> SnailFishNumber {
+    // errors carry the char index they were found at, running off the end is usize::MAX
+    fn from_chars(iter: &mut Peekable<CharIndices>) -> Result<SnailFishNumber, (usize, String)> {
+        let end = || (usize::MAX, String::from("EOL mid parse"));
         match iter.next() {
             None => {
-                panic!("cannot parse nothing");
+                return Err(end());
             }
-            Some(c) => {
+            Some((i, c)) => {
                 if c == '[' {
                     // this is me
-                } else if c >= '0' && c <= '9' {
+                } else if c.is_ascii_digit() {
                     // first char is a number
-                    return SnailFishNumber::from_char_num(c);
+                    return Ok(SnailFishNumber::from_char_num(c));
                 } else {
-                    panic!("invalid beginning: {}", c);
+                    return Err((i, format!("invalid beginning: {}", c)));
                 }
             }
         }
 
-        let left = SnailFishNumber::from_chars(iter);
+        let left = SnailFishNumber::from_chars(iter)?;
         match iter.next() {
             None => {
-                panic!("EOL mid parse");
+                return Err(end());
             }
-            Some(c) => {
+            Some((i, c)) => {
                 if c == ',' {
                     // expected
                 } else {
-                    panic!("all snailfish numbers are pairs: {}", c);
+                    return Err((i, format!("all snailfish numbers are pairs: {}", c)));
                 }
             }
         }
-        let right = SnailFishNumber::from_chars(iter);
+        let right = SnailFishNumber::from_chars(iter)?;
 
         match iter.next() {
             None => {
-                panic!("EOL mid parse");
+                return Err(end());
             }
-            Some(c) => {
+            Some((i, c)) => {
                 if c == ']' {
                     // expected
                 } else {
-                    panic!("all snailfish numbers are pairs (ie 2): {}", c);
+                    return Err((i, format!("all snailfish numbers are pairs (ie 2): {}", c)));
                 }
             }
         }
 
-        SnailFishNumber {
+        Ok(SnailFishNumber {
             left: Some(Box::new(left)),
             right: Some(Box::new(right)),
             literal: None
-        }
+        })
     }
 
     fn from_char_num(c: char) -> SnailFishNumber {
@@ -159,14 +178,14 @@ impl SnailFishNumber {
         loop {
             loop {
                 let explode = self.explode(1);
-                if !explode.is_some() {break}
+                if explode.is_none() {break}
             }
             let splitted = self.split();
             let mut any_explodes = false;
             loop {
                 let explode = self.explode(1);
                 any_explodes |= explode.is_some();
-                if !explode.is_some() {break}
+                if explode.is_none() {break}
             }
             if !splitted && !any_explodes {break}
         }
@@ -274,11 +293,11 @@ impl fmt::Debug for SnailFishNumber {
             f.write_fmt(format_args!("{}", self.literal.unwrap()))
         } else {
             let mut list = f.debug_list();
-            if self.left.is_some() {
-                list.entry(self.left.as_ref().unwrap());
+            if let Some(left) = &self.left {
+                list.entry(left);
             }
-            if self.right.is_some() {
-                list.entry(self.right.as_ref().unwrap());
+            if let Some(right) = &self.right {
+                list.entry(right);
             }
             list.finish()
         }
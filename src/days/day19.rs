@@ -1,103 +1,128 @@
-use super::super::day::Day;
+use super::super::day::{Answer, CancelToken, Day, ParseError};
+use super::super::geometry::{Point3, Rotation};
+use super::super::parse::Parser;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
 use petgraph::prelude::*;
 use rayon::prelude::*;
 
-type Point = (isize, isize, isize);
-type PointSet = HashSet<Point>;
-type Translator = fn(&Point) -> Point;
-type Converter = (Point, Translator);
+const DAY: usize = 19;
+
+type PointSet = HashSet<Point3>;
+// rotate then offset, from one scanner's view to another's
+type Converter = (Point3, Rotation);
 type DirectedConverters = HashMap<(usize, usize), Converter>;
-type FieldGetter = fn(&Point) -> isize;
+type FieldGetter = fn(&Point3) -> isize;
 
 pub struct Day19 {
     scanners: Vec<ScannerView>,
+    // filled by prepare, or else by whichever part needs it first
+    alignment: OnceLock<Alignment>
+}
+
+// which scanners overlap, and how to convert between each overlapping pair
+struct Alignment {
     graph: DiGraphMap<usize, ()>,
     converters: DirectedConverters
 }
 
 impl Day19 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
         let mut scanners = Vec::new();
-        let mut scanner_idx = 0;
-        let mut curr_view = ScannerView::new(scanner_idx);
-        scanner_idx += 1;
-        for line in content.lines() {
-            if line.contains("---") {
-                if curr_view.beacons.len() > 0 {
-                    scanners.push(curr_view);
-                    curr_view = ScannerView::new(scanner_idx);
-                    scanner_idx += 1;
-                }
-            } else if line.len() > 0 {
-                curr_view.parse_line_add(line);
+        for (scanner_idx, block) in parser.blocks().into_iter().enumerate() {
+            let mut lines = block.lines().map(str::trim);
+            let header = lines.next().unwrap();
+            parser.prefixed(header, "--- scanner")?;
+            let mut curr_view = ScannerView::new(scanner_idx);
+            for line in lines {
+                curr_view.parse_line_add(&parser, line)?;
             }
-        }
-        if curr_view.beacons.len() > 0 {
             scanners.push(curr_view);
         }
+        if scanners.is_empty() {
+            return Err(parser.eof("no scanners"));
+        }
 
-        let mut graph: DiGraphMap<usize, ()> = DiGraphMap::new();
-        let mut converters: DirectedConverters = HashMap::new();
+        Ok(Box::new(Day19 {
+            scanners,
+            alignment: OnceLock::new()
+        }))
+    }
 
-        let perms: Vec<Vec<ScannerView>> = scanners.clone().into_iter()
+    // aligning every pair of scanners is the slow part. None once cancel fires,
+    // what was aligned by then is only part of the picture
+    fn align(&self, cancel: &CancelToken) -> Option<Alignment> {
+        let perms: Vec<Vec<&ScannerView>> = self.scanners.iter()
             .permutations(2)
             .collect();
+        let rotations = Rotation::all();
         let results: Vec<(usize, usize, Converter)> = perms.par_iter()
-            .map(|pair| {
-                match pair[0].find_alignment(&pair[1]) {
-                    None => None,
-                    Some(converter) => {
-                        Some((pair[0].id, pair[1].id, converter))
-                    }
-                }
-            })
-            .filter_map(|opt| opt)
+            .filter(|_| !cancel.is_cancelled())
+            .filter_map(|pair| pair[0].find_alignment(pair[1], &rotations)
+                .map(|converter| (pair[0].id, pair[1].id, converter)))
             .collect();
+        if cancel.is_cancelled() {
+            return None;
+        }
 
+        let mut alignment = Alignment {
+            graph: DiGraphMap::new(),
+            converters: HashMap::new()
+        };
         for (from_id, to_id, backward_converter) in results {
-            graph.add_edge(from_id, to_id, ());
-            converters.insert((to_id, from_id), backward_converter);
+            alignment.graph.add_edge(from_id, to_id, ());
+            alignment.converters.insert((to_id, from_id), backward_converter);
         }
+        Some(alignment)
+    }
 
-        Ok(Box::new(Day19 {
-            scanners,
-            graph,
-            converters
-        }))
+    fn alignment(&self) -> &Alignment {
+        self.alignment.get_or_init(|| self.align(&CancelToken::never()).unwrap())
     }
 }
 
 impl Day for Day19 {
-    fn part1(&mut self) -> isize {
+    fn prepare(&self, cancel: &CancelToken) {
+        if self.alignment.get().is_none() {
+            if let Some(alignment) = self.align(cancel) {
+                let _ = self.alignment.set(alignment); // a part on another thread may have got there first
+            }
+        }
+    }
+
+    fn part1(&self) -> Answer {
+        let alignment = self.alignment();
         let mut visits: HashSet<usize> = HashSet::new();
         reverse_translate(
-            &self.graph,
+            &alignment.graph,
             &mut visits,
             0,
             None,
             &self.scanners,
-            &self.converters,
+            &alignment.converters,
             |id, sets| sets[id].beacons.clone()
-        ).len() as isize
+        ).len().into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
+        let alignment = self.alignment();
         let mut visits: HashSet<usize> = HashSet::new();
         let scanners = reverse_translate(
-            &self.graph,
+            &alignment.graph,
             &mut visits,
             0,
             None,
             &self.scanners,
-            &self.converters,
-            |_, _| HashSet::from([(0isize, 0isize, 0isize)])
+            &alignment.converters,
+            |_, _| HashSet::from([Point3::ORIGIN])
         );
         scanners.iter().permutations(2)
-            .map(|pair| (pair[0].0 - pair[1].0).abs() + (pair[0].1 - pair[1].1).abs() + (pair[0].2 - pair[1].2).abs())
+            .map(|pair| pair[0].manhattan(pair[1]))
             .max()
-            .unwrap() as isize
+            .unwrap_or(0) // a lone scanner is no distance from itself
+            .into()
     }
 }
 
@@ -127,8 +152,7 @@ fn reverse_translate(
                 None => panic!("no converter for {}->{}", id, to_id),
                 Some(converter) => {
                     combined_points.iter()
-                        .map(converter.1)
-                        .map(|(x, y, z)| (x + converter.0.0, y + converter.0.1, z + converter.0.2))
+                        .map(|point| converter.1.apply(point) + converter.0)
                         .collect()
                 }
             }
@@ -149,17 +173,16 @@ impl ScannerView {
             beacons: HashSet::new()
         }
     }
-    fn parse_line_add(&mut self, line: &str) {
-        self.beacons.insert(line.split(',')
-            .map(|word| word.parse().unwrap())
-            .tuples()
-            .next()
-            .unwrap());
+    fn parse_line_add(&mut self, parser: &Parser, line: &str) -> Result<(), ParseError> {
+        let coords = parser.expect_len(parser.nums(line, ',')?, 3, line, "coordinates")?;
+        self.beacons.insert(Point3::new(coords[0], coords[1], coords[2]));
+        Ok(())
     }
-    fn find_alignment(&self, other: &ScannerView) -> Option<Converter> {
-        for (forward, backward) in TRANSLATORS.iter().tuples() {
+    // the converter from other's view back to this one, if at least 12 beacons line up under some rotation
+    fn find_alignment(&self, other: &ScannerView, rotations: &[Rotation]) -> Option<Converter> {
+        for rotation in rotations {
             let translated: PointSet = self.beacons.iter()
-                .map(forward)
+                .map(|point| rotation.apply(point))
                 .collect();
 
             for x_diff in cartesian_diff_filter(get_pos_counts(&translated, get_x), get_pos_counts(&other.beacons, get_x)) {
@@ -176,7 +199,8 @@ impl ScannerView {
 
                         if l_matches.len() < 12 {continue}
 
-                        return Some((backward(&(x_diff, y_diff, z_diff)), *backward))
+                        let backward = rotation.inverse();
+                        return Some((backward.apply(&Point3::new(x_diff, y_diff, z_diff)), backward))
                     }
                 }
             }
@@ -185,45 +209,16 @@ impl ScannerView {
     }
 }
 
-// forward, backward
-const TRANSLATORS: [Translator; 48] = [
-    |&(x, y, z)| (x, y, z), |&(x, y, z)| (x, y, z), // original
-    |&(x, y, z)| (x, z, -y), |&(x, y, z)| (x, -z, y), // 90 about x
-    |&(x, y, z)| (x, -y, -z), |&(x, y, z)| (x, -y, -z), // 180 about x
-    |&(x, y, z)| (x, -z, y), |&(x, y, z)| (x, z, -y), // 260 about x
-    |&(x, y, z)| (z, y, -x), |&(x, y, z)| (-z, y, x), // 90 about y (new section)
-    |&(x, y, z)| (-y, z, -x), |&(x, y, z)| (-z, -x, y), // and then 90 about old x
-    |&(x, y, z)| (-z, -y, -x), |&(x, y, z)| (-z, -y, -x), // and then 180 about old x
-    |&(x, y, z)| (y, -z, -x), |&(x, y, z)| (-z, x, -y), // and then 260 about old x
-    |&(x, y, z)| (-x, y, -z), |&(x, y, z)| (-x, y, -z), // and then 180 about old y (new section)
-    |&(x, y, z)| (-x, z, y), |&(x, y, z)| (-x, z, y), // and then 90 about old x
-    |&(x, y, z)| (-x, -y, z), |&(x, y, z)| (-x, -y, z), // and then 180 about old x
-    |&(x, y, z)| (-x, -z, -y), |&(x, y, z)| (-x, -z, -y), // and then 260 about old x
-    |&(x, y, z)| (-z, y, x), |&(x, y, z)| (z, y, -x), // and then 260 about y (new section)
-    |&(x, y, z)| (-y, -z, x), |&(x, y, z)| (z, -x, -y), // and then 90 about old x
-    |&(x, y, z)| (z, -y, x), |&(x, y, z)| (z, -y, x), // and then 180 about old x
-    |&(x, y, z)| (y, z, x), |&(x, y, z)| (z, x, y), // and then 260 about old x
-    |&(x, y, z)| (-y, x, z), |&(x, y, z)| (y, -x, z), // (from original) 90 about z
-    |&(x, y, z)| (z, x, y), |&(x, y, z)| (y, z, x), // and then 90 about old x
-    |&(x, y, z)| (y, x, -z), |&(x, y, z)| (y, x, -z), // and then 180 about old x
-    |&(x, y, z)| (-z, x, -y), |&(x, y, z)| (y, -z, -x), // and then 260 about old x
-    // (from original) 180 about z - already covered
-    |&(x, y, z)| (y, -x, z), |&(x, y, z)| (-y, x, z), // (from original) 260 about z
-    |&(x, y, z)| (z, -x, -y), |&(x, y, z)| (-y, -z, x), // and then 90 about old x
-    |&(x, y, z)| (-y, -x, -z), |&(x, y, z)| (-y, -x, -z), // and then 180 about old x
-    |&(x, y, z)| (-z, -x, y), |&(x, y, z)| (-y, z, -x), // and then 260 about old x
-];
-
-fn get_x(p: &Point) -> isize {
-    p.0
+fn get_x(p: &Point3) -> isize {
+    p.x
 }
 
-fn get_y(p: &Point) -> isize {
-    p.1
+fn get_y(p: &Point3) -> isize {
+    p.y
 }
 
-fn get_z(p: &Point) -> isize {
-    p.2
+fn get_z(p: &Point3) -> isize {
+    p.z
 }
 
 fn get_pos_counts(points: &PointSet, field: FieldGetter) -> HashMap<isize, usize> {
@@ -263,4 +258,26 @@ fn field_intersect(l: &PointSet, r: &PointSet, l_diff: isize, field: FieldGetter
             .cloned()
             .collect()
     )
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day19;
+
+    fn error_at(content: &str) -> (usize, usize, String) {
+        let error = Day19::from_content(content).err().unwrap();
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn bad_scanner_header() {
+        assert_eq!(error_at("--- scanner 0 ---\n1,2,3\n\n-- scanner 1 ---\n4,5,6\n"),
+                   (4, 1, String::from("expected \"--- scanner\"")));
+    }
+
+    #[test]
+    fn bad_beacons() {
+        assert_eq!(error_at("--- scanner 0 ---\n1,2,3\n4,5\n"), (3, 1, String::from("expected 3 coordinates")));
+        assert_eq!(error_at("--- scanner 0 ---\n1,2,3\n4,y,6\n"), (3, 3, String::from("not a num: y")));
+    }
+}
@@ -1,5 +1,7 @@
-use super::super::day::Day;
-use itertools::Itertools;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
+
+const DAY: usize = 2;
 
 pub struct Day2 {
     instructions: Vec<Instruction>
@@ -17,13 +19,14 @@ struct Instruction {
 }
 
 impl Day2 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
         Ok(Box::new(Day2 {
-            instructions: content.split_whitespace()
-                .tuples()
-                .map(|(sdir, sqty)| {
-                    let quantity = sqty.parse().expect("not a num");
-                    match sdir {
+            instructions: parser.lines()
+                .map(|line| {
+                    let (sdir, sqty) = parser.pair(line, " ", "<direction> <quantity>")?;
+                    let quantity = parser.num(sqty)?;
+                    Ok(match sdir {
                         "forward" => Instruction {
                             direction: Direction::Forward,
                             quantity
@@ -36,17 +39,17 @@ impl Day2 {
                             direction: Direction::Down,
                             quantity
                         },
-                        _ => panic!("unsupported direction {}", sdir)
-                    }
+                        _ => return Err(parser.error(sdir, format!("unsupported direction {}", sdir)))
+                    })
                 })
-                .collect()
+                .collect::<Result<_, _>>()?
         }))
     }
 }
 
 impl Day for Day2 {
 
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let (x, y): (isize, isize) = self.instructions.iter().fold((0, 0), |(x, y), instr| {
             match instr.direction {
                 Direction::Forward => (x + instr.quantity, y),
@@ -54,10 +57,10 @@ impl Day for Day2 {
                 Direction::Down => (x, y - instr.quantity)
             }
         });
-        x * -y
+        Answer::Int(x * -y)
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let (h, d, _) = self.instructions.iter().fold((0, 0, 0), |(h, d, dfac), instr| {
             match instr.direction {
                 Direction::Forward => (h + instr.quantity, d + instr.quantity * dfac, dfac),
@@ -65,6 +68,6 @@ impl Day for Day2 {
                 Direction::Down => (h, d, dfac + instr.quantity)
             }
         });
-        h * d
+        Answer::Int(h * d)
     }
 }
\ No newline at end of file
@@ -1,33 +1,45 @@
-use itertools::Itertools;
-use super::super::day::Day;
-use array2d::Array2D;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::grid::Grid;
+use super::super::parse::Parser;
 
-type Idx = (isize, isize);
+const DAY: usize = 20;
+const ENHANCER_LEN: usize = 512;
+const PIXELS: &str = ".#";
+// a pixel's 3x3 group, row by row
+const GROUP: [(isize, isize); 9] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 0), (0, 1), (1, -1), (1, 0), (1, 1)];
 
 pub struct Day20 {
     image: Image,
 }
 
 impl Day20 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let mut lines = content.lines();
-        let enhancer: Vec<char> = lines.next().unwrap().chars().collect();
-        lines.next();
-        let base_rows = lines.map(|line| {
-            line.chars().collect()
-        }).collect::<Vec<Vec<char>>>();
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        if content.trim().is_empty() {
+            return Err(parser.eof("no enhancement data"));
+        }
+        let (enhancer_line, image_section) = content.split_once('\n').unwrap_or((content, ""));
+        let enhancer_line = enhancer_line.trim();
+        if let Some((i, c)) = enhancer_line.char_indices().find(|(_, c)| !PIXELS.contains(*c)) {
+            return Err(parser.error(&enhancer_line[i..], format!("not a pixel: {}", c)));
+        }
+        let enhancer = parser.expect_len(enhancer_line.chars().collect(), ENHANCER_LEN, enhancer_line, "enhancement pixels")?;
+        let cells = parser.char_grid(image_section, PIXELS, "pixel")?;
         Ok(Box::new(Day20 {
-            image: Image::from(Array2D::from_rows(&base_rows), enhancer)
+            image: Image {
+                cells: cells.with_background('.'),
+                enhancement_data: enhancer
+            }
         }))
     }
 }
 
 impl Day for Day20 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         self.image.enhance().enhance().pixel_count()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let mut image = self.image.clone();
         for _ in 0..50 {
             image = image.enhance();
@@ -36,111 +48,53 @@ impl Day for Day20 {
     }
 }
 
+// the background is every pixel out to infinity, they all enhance the same way
 #[derive(Clone)]
 struct Image {
-    cells: Array2D<char>,
-    enhancement_data: Vec<char>,
-    infinity: char
+    cells: Grid<char>,
+    enhancement_data: Vec<char>
 }
 
 impl Image {
-    fn from(cells: Array2D<char>, enhancement_data: Vec<char>) -> Self {
-        Image {
-            cells,
-            enhancement_data,
-            infinity: '.'
-        }
+    fn infinity(&self) -> char {
+        *self.cells.background().unwrap()
     }
 
     fn enhance(&self) -> Self {
         let expand_radius = 1usize;
-        let new_infinity = if self.infinity == '.' {
+        let new_infinity = if self.infinity() == '.' {
             self.enhancement_data[0]
         } else {
             self.enhancement_data[self.enhancement_data.len() - 1]
         };
-        let mut new_cells = Array2D::filled_with(new_infinity,
-                                                 self.cells.num_rows() + (2 * expand_radius),
-                                                 self.cells.num_columns() + (2 * expand_radius));
-        for i in 0..new_cells.num_rows() {
-            for j in 0..new_cells.num_columns() {
-                let old_i = (i as isize) - (expand_radius as isize);
-                let old_j = (j as isize) - (expand_radius as isize);
-                let values: Vec<char> = self.build_group(&(old_i, old_j)).iter()
-                    .map(|p| self.get_cell(p))
-                    .collect();
-                new_cells[(i, j)] = self.get_enhancement_cell(values, new_infinity);
-            }
-        }
+        let new_cells = Grid::from_fn(self.cells.rows() + (2 * expand_radius),
+                                      self.cells.cols() + (2 * expand_radius),
+                                      |(i, j)| {
+            let old_i = (i as isize) - (expand_radius as isize);
+            let old_j = (j as isize) - (expand_radius as isize);
+            self.enhancement_data[self.group_idx(old_i, old_j)]
+        });
         Image {
-            cells: new_cells,
-            enhancement_data: self.enhancement_data.clone(),
-            infinity: new_infinity
+            cells: new_cells.with_background(new_infinity),
+            enhancement_data: self.enhancement_data.clone()
         }
     }
 
-    fn validate_idx(&self, p: &Idx) -> Option<(usize, usize)> {
-        if p.0 > -1 && p.1 > -1
-            && (p.0 as usize) < self.cells.num_rows()
-            && (p.1 as usize) < self.cells.num_columns()
-        {
-            Some((p.0 as usize, p.1 as usize))
-        } else {
-            None
-        }
+    // the group around a pixel read as a binary number, lit pixels the ones
+    fn group_idx(&self, i: isize, j: isize) -> usize {
+        GROUP.iter()
+            .map(|(i_d, j_d)| *self.cells.get_signed(i + i_d, j + j_d).unwrap())
+            .fold(0, |idx, c| (idx << 1) | if c == '#' { 1 } else { 0 })
     }
 
-    fn get_cell(&self, p: &Idx) -> char {
-        match self.validate_idx(p) {
-            None => self.infinity,
-            Some(p) => self.cells[p]
-        }
-    }
-
-    fn get_enhancement_cell(&self, chars: Vec<char>, default: char) -> char {
-        if chars.len() > 0 {
-            let b_str = chars.iter().map(|c| match *c {
-                '.' => "0",
-                '#' => "1",
-                _ => panic!("invalid char {}", c)
-            }).join("");
-            let idx = usize::from_str_radix(&b_str, 2).unwrap();
-            self.enhancement_data[idx]
-        } else {
-            default
-        }
-    }
-
-    fn pixel_count(&self) -> isize {
-        if self.infinity == '#' {
-            -1
+    fn pixel_count(&self) -> Answer {
+        if self.infinity() == '#' {
+            Answer::NotApplicable // lit out to infinity, there is no count
         } else {
-            self.cells.elements_row_major_iter()
-                .map(|c| match *c {
-                    '.' => 0,
-                    '#' => 1,
-                    _ => panic!("invalid char {}", c)
-                }).sum::<usize>() as isize
-        }
-    }
-
-    fn build_group(&self, p: &Idx) -> Vec<Idx> {
-        let idxs: Vec<Idx> = [
-            (p.0 - 1, p.1 - 1), // up left
-            (p.0 - 1, p.1), // up
-            (p.0 - 1, p.1 + 1), // up right
-            (p.0, p.1 - 1), // left
-            (p.0, p.1), // current
-            (p.0, p.1 + 1), // right
-            (p.0 + 1, p.1 - 1), // down left
-            (p.0 + 1, p.1), // down
-            (p.0 + 1, p.1 + 1) // down right
-        ].into_iter().collect();
-        if idxs.iter().filter_map(|p| self.validate_idx(p)).count() > 0 {
-            idxs
-        } else {
-            Vec::new()
+            self.cells.values()
+                .filter(|c| **c == '#')
+                .count()
+                .into()
         }
     }
 }
-
@@ -1,10 +1,10 @@
 use std::cmp::max;
 use std::collections::HashMap;
 
-use itertools::Itertools;
-
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 
+const DAY: usize = 21;
 const DIE_ROLLS: usize = 3;
 
 pub struct Day21 {
@@ -12,19 +12,34 @@ pub struct Day21 {
 }
 
 impl Day21 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let pos: Vec<usize> = parser.lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                let (player, n) = parser.pair(line, "starting position:", "Player <n> starting position: <pos>")?;
+                let player = parser.prefixed(player, "Player")?;
+                if parser.num::<usize>(player)? != idx + 1 {
+                    return Err(parser.error(player, format!("expected player {}", idx + 1)));
+                }
+                let p: usize = parser.num(n)?;
+                if !(1..=BOARD_SIZE).contains(&p) {
+                    return Err(parser.error(n, format!("positions are 1 to {}", BOARD_SIZE)));
+                }
+                Ok(p - 1) // make mod nice
+            })
+            .collect::<Result<_, _>>()?;
+        if pos.len() != 2 {
+            return Err(parser.eof("expected 2 players"));
+        }
         Ok(Box::new(Day21 {
-            pos: content.split([':', '\n'])
-                .tuples()
-                .filter_map(|(_, n)| n.trim().parse().ok())
-                .map(|p: usize| p - 1) // make mod nice
-                .collect()
+            pos
         }))
     }
 }
 
 impl Day for Day21 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let end_score = 1000usize;
         let mut die = DetDie::new(100);
         let mut pos = self.pos.clone();
@@ -40,12 +55,12 @@ impl Day for Day21 {
                 }
             }
         }
-        (scores.iter().min().unwrap() * die.rolls()) as isize
+        (scores.iter().min().unwrap() * die.rolls()).into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let wins = dirac_wins((self.pos[0], 0, self.pos[1], 0, true), &mut HashMap::new());
-        max(wins.0, wins.1) as isize
+        max(wins.0, wins.1).into()
     }
 }
 
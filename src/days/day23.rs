@@ -1,88 +1,50 @@
-use std::collections::{BTreeSet, HashMap};
 use itertools::Itertools;
-use rayon::prelude::*;
+use log::debug;
 
-use super::super::day::Day;
+use super::super::day::{Answer, CancelToken, Day, ParseError};
+use super::super::parse::Parser;
+use super::super::search;
+
+const DAY: usize = 23;
 
 pub struct Day23 {
     burrow: Burrow2,
 }
 
 impl Day23 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
         Ok(Box::new(Day23 {
-            burrow: Burrow2::from_content(content)
+            burrow: Burrow2::from_content(content)?
         }))
     }
 }
 
 impl Day for Day23 {
-    fn part1(&mut self) -> isize {
-        try_all_moves(self.burrow.clone(), &mut HashMap::new(), &mut BTreeSet::new()).unwrap() as isize
+    fn part1(&self) -> Answer {
+        self.part1_until(&CancelToken::never())
     }
 
-    fn part2(&mut self) -> isize {
-        0
+    fn part2(&self) -> Answer {
+        Answer::NotApplicable
     }
-}
 
-fn min_opt(l: Option<usize>, r: Option<usize>) -> Option<usize> {
-    match l {
-        None => {
-            match r {
-                None => None,
-                Some(rv) => Some(rv)
-            }
-        }
-        Some(lv) => {
-            match r {
-                None => Some(lv),
-                Some(rv) => {
-                    Some(lv.min(rv))
-                }
+    // once cancelled there are no more moves to try, so the search runs dry and gives up
+    fn part1_until(&self, cancel: &CancelToken) -> Answer {
+        let solution = search::dijkstra(self.burrow.clone(),
+                                        |burrow| if cancel.is_cancelled() { Vec::new() } else { burrow.moves() },
+                                        Burrow2::is_solved);
+        match solution {
+            Some(path) if !cancel.is_cancelled() => {
+                debug!("solved in {} moves:\n{}", path.states.len() - 1,
+                       path.states.iter().map(Burrow2::display).join("\n\n"));
+                path.cost.into()
             }
+            _ => Answer::NotApplicable
         }
     }
 }
 
-fn try_all_moves(burrow: Burrow2, memo: &mut HashMap<Burrow2, Option<usize>>, solutions: &mut BTreeSet<usize>) -> Option<usize> {
-    // eprintln!("try\n{}", burrow.display());
-    match memo.get(&burrow) {
-        Some(min_energy) => *min_energy,
-        None => {
-            if burrow.is_solved() {
-                let cost = burrow.cost;
-                eprintln!("solution: {}", cost);
-                memo.insert(burrow, Some(cost));
-                solutions.insert(cost);
-                Some(cost)
-            } else {
-                let mut min_energy: Option<usize> = None; // all moves could fail
-
-                for next_burrow in (0..burrow.rooms.len()).map(|room_idx| {
-                    (0..burrow.hallway.len()).map(move |hallway_idx| (room_idx, hallway_idx))
-                })
-                    .flatten()
-                    .collect::<Vec<(usize, usize)>>()
-                    .par_iter() // thread the valid move calculations
-                    .flat_map(|(room_idx, hallway_idx)| vec![
-                        burrow.move_room_to_hallway(*room_idx, *hallway_idx),
-                        burrow.move_hallway_to_room(*hallway_idx, *room_idx),
-                    ])
-                    .filter_map(|opt| opt)
-                    .collect::<Vec<Burrow2>>().into_iter() // un-thread
-                {
-                    if solutions.is_empty() || next_burrow.cost < *solutions.first().unwrap() {
-                        min_energy = min_opt(min_energy, try_all_moves(next_burrow, memo, solutions));
-                    }
-                }
-
-                memo.insert(burrow, min_energy);
-                min_energy
-            }
-        }
-    }
-}
+const AMPHIPOD_TYPES: usize = 4;
 
 type Slots2 = Vec<usize>;
 
@@ -124,27 +86,37 @@ impl Room2 {
 struct Burrow2 {
     hallway: Slots2,
     rooms: Vec<Room2>,
-    cost: usize,
 }
 
 impl Burrow2 {
-    fn from_content(content: &str) -> Self {
+    fn from_content(content: &str) -> Result<Self, ParseError> {
+        let parser = Parser::new(DAY, content);
         let mut lines = content.lines();
         lines.next(); // header
-        let hallway_len = lines.next().unwrap().chars().filter(|c| *c == '.').count();
+        let hallway_line = lines.next()
+            .ok_or_else(|| parser.eof("no hallway"))?;
+        let hallway_len = hallway_line.chars().filter(|c| *c == '.').count();
+        if hallway_len < 1 {
+            return Err(parser.error(hallway_line, "empty hallway"));
+        }
 
         let mut rooms: Vec<Room2> = Vec::new();
         for room_line in lines {
             let mut room_i = 0;
-            for (i, c) in room_line.chars().enumerate() {
+            // anything but ascii is turned away before it is passed, so i is a column as well as a byte offset
+            for (i, c) in room_line.char_indices() {
                 let pod = match c {
                     'A' => 1,
                     'B' => 10,
                     'C' => 100,
                     'D' => 1000,
-                    _ => continue
+                    '#' | ' ' | '.' => continue,
+                    _ => return Err(parser.error(&room_line[i..], format!("not an amphipod: {}", c)))
                 };
                 if room_i >= rooms.len() {
+                    if i < 1 || i > hallway_len {
+                        return Err(parser.error(&room_line[i..], "room is outside the hallway"));
+                    }
                     rooms.push(Room2::new(i - 1));
                 }
                 rooms[room_i].slots.push(pod);
@@ -152,13 +124,19 @@ impl Burrow2 {
             }
         }
 
+        if rooms.len() != AMPHIPOD_TYPES {
+            return Err(parser.eof(format!("expected {} rooms", AMPHIPOD_TYPES)));
+        }
+        if rooms.iter().any(|room| room.slots.len() != rooms[0].slots.len()) {
+            return Err(parser.eof("rooms differ in depth"));
+        }
+
         rooms.iter_mut().for_each(|room| room.slots.reverse());
 
-        Self {
+        Ok(Self {
             hallway: vec![0; hallway_len],
             rooms,
-            cost: 0,
-        }
+        })
     }
 
     fn is_solved(&self) -> bool {
@@ -167,13 +145,25 @@ impl Burrow2 {
                 return false;
             }
         }
-        return self.hallway.iter().sum::<usize>() == 0;
+        self.hallway.iter().sum::<usize>() == 0
+    }
+
+    // every burrow one amphipod move away, with the energy the move takes
+    fn moves(&self) -> Vec<(Self, usize)> {
+        (0..self.rooms.len())
+            .flat_map(|room_idx| (0..self.hallway.len()).map(move |hallway_idx| (room_idx, hallway_idx)))
+            .flat_map(|(room_idx, hallway_idx)| [
+                self.move_room_to_hallway(room_idx, hallway_idx),
+                self.move_hallway_to_room(hallway_idx, room_idx),
+            ])
+            .flatten()
+            .collect()
     }
 
-    fn move_hallway_to_room(&self, hallway_idx: usize, room_idx: usize) -> Option<Self> {
+    fn move_hallway_to_room(&self, hallway_idx: usize, room_idx: usize) -> Option<(Self, usize)> {
         let pod = self.hallway[hallway_idx];
         if pod == 0 { return None; }
-        if pod.log10() != (room_idx as u32) { return None; }
+        if pod.ilog10() != (room_idx as u32) { return None; }
         let room = &self.rooms[room_idx];
         if !room.accepts(pod) { return None; }
         let (l, r) = if room.location < hallway_idx {
@@ -195,14 +185,10 @@ impl Burrow2 {
             }
         }
         let move_cost = (r - l + 1 + room_dist) * pod;
-        Some(Self {
-            hallway,
-            rooms,
-            cost: self.cost + move_cost,
-        })
+        Some((Self { hallway, rooms }, move_cost))
     }
 
-    fn move_room_to_hallway(&self, room_idx: usize, hallway_idx: usize) -> Option<Self> {
+    fn move_room_to_hallway(&self, room_idx: usize, hallway_idx: usize) -> Option<(Self, usize)> {
         if self.rooms.iter().any(|room| room.location == hallway_idx) { return None; }
         let room = &self.rooms[room_idx];
         if !room.can_remove(10usize.pow(room_idx as u32)) { return None; } // TODO too aggressive an optimization?
@@ -225,14 +211,9 @@ impl Burrow2 {
         }
         hallway[hallway_idx] = pod;
         let move_cost = (r - l + room_dist) * pod;
-        Some(Self {
-            hallway,
-            rooms,
-            cost: self.cost + move_cost,
-        })
+        Some((Self { hallway, rooms }, move_cost))
     }
 
-    #[allow(dead_code)]
     fn display(&self) -> String {
         let mut lines: Vec<String> = Vec::new();
 
@@ -274,4 +255,25 @@ impl Burrow2 {
 
 fn min_max<T: Ord + Copy>(l: T, r: T) -> (T, T) {
     (l.min(r), l.max(r))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::Day23;
+
+    fn error_at(content: &str) -> (usize, usize, String) {
+        let error = Day23::from_content(content).err().unwrap();
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn bad_rooms() {
+        assert_eq!(error_at("#############\n#...........#\n###B#C#X#D###\n  #A#D#C#A#\n  #########\n"),
+                   (3, 8, String::from("not an amphipod: X")));
+        assert_eq!(error_at("#############\n#..#\n###B#C#B#D###\n"), (3, 4, String::from("room is outside the hallway")));
+    }
+
+    #[test]
+    fn empty_hallway() {
+        assert_eq!(error_at("####\n####\n"), (2, 1, String::from("empty hallway")));
+    }
+}
@@ -1,27 +1,42 @@
-use super::super::day::Day;
-use rayon::prelude::*;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
+use log::debug;
+
+const DAY: usize = 24;
 
 pub struct Day24 {
     ops: Vec<Op>
 }
 
 impl Day24 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
         Ok(Box::new(Day24 {
-            ops: content.lines().map(Op::from_str).collect()
+            ops: parser.lines()
+                .map(|line| Op::from_str(&parser, line))
+                .collect::<Result<_, _>>()?
         }))
     }
+
+    // the hand-found number is only the answer if this program accepts it
+    fn checked_model_number(&self, model_number: &str) -> Answer {
+        if Alu::new().verify(&self.ops, model_number.chars().collect()) {
+            model_number.parse::<isize>().unwrap().into()
+        } else {
+            Answer::NotApplicable
+        }
+    }
 }
 
 impl Day for Day24 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         // analysis and algebra done by "hand"
-        ALU::new().verify(&self.ops, "91897399498995".chars().collect()) as isize
+        self.checked_model_number("91897399498995")
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         // analysis and algebra done by "hand"
-        ALU::new().verify(&self.ops, "51121176121391".chars().collect()) as isize
+        self.checked_model_number("51121176121391")
     }
 }
 
@@ -31,9 +46,12 @@ struct Register {
 }
 
 impl Register {
-    fn from_str(word: &str) -> Self {
-        Self {
-            id: word.chars().next().unwrap()
+    fn from_str(parser: &Parser, word: &str) -> Result<Self, ParseError> {
+        match word {
+            "w" | "x" | "y" | "z" => Ok(Self {
+                id: word.chars().next().unwrap()
+            }),
+            _ => Err(parser.error(word, format!("no such register {}", word)))
         }
     }
     fn get_index(&self) -> usize {
@@ -48,13 +66,13 @@ enum RValue {
 }
 
 impl RValue {
-    fn from_str(word: &str) -> Self {
+    fn from_str(parser: &Parser, word: &str) -> Result<Self, ParseError> {
         match word.parse::<isize>() {
             Ok(literal) => {
-                RValue::Literal(literal)
+                Ok(RValue::Literal(literal))
             }
             Err(_) => {
-                RValue::Register(Register::from_str(word))
+                Ok(RValue::Register(Register::from_str(parser, word)?))
             }
         }
     }
@@ -71,28 +89,33 @@ enum Op {
 }
 
 impl Op {
-    fn from_str(words: &str) -> Self {
-        let parts: Vec<&str> = words.split(' ').collect();
-        let reg = Register::from_str(parts[1]);
-        match parts[0] {
-            "inp" => Op::Input(reg),
-            "add" => Op::Add(reg, RValue::from_str(parts[2])),
-            "mul" => Op::Multiply(reg, RValue::from_str(parts[2])),
-            "div" => Op::Divide(reg, RValue::from_str(parts[2])),
-            "mod" => Op::Mod(reg, RValue::from_str(parts[2])),
-            "eql" => Op::Equal(reg, RValue::from_str(parts[2])),
-            _ => panic!("no such op {}", parts[0])
+    fn from_str(parser: &Parser, words: &str) -> Result<Self, ParseError> {
+        let parts: Vec<&str> = words.split_whitespace().collect();
+        let operands = if parts.first() == Some(&"inp") { 1 } else { 2 };
+        if parts.len() != operands + 1 {
+            return Err(parser.error(words, format!("expected {} operands", operands)));
         }
+        let reg = Register::from_str(parser, parts[1])?;
+        let r_value = || RValue::from_str(parser, parts[2]);
+        Ok(match parts[0] {
+            "inp" => Op::Input(reg),
+            "add" => Op::Add(reg, r_value()?),
+            "mul" => Op::Multiply(reg, r_value()?),
+            "div" => Op::Divide(reg, r_value()?),
+            "mod" => Op::Mod(reg, r_value()?),
+            "eql" => Op::Equal(reg, r_value()?),
+            _ => return Err(parser.error(parts[0], format!("no such op {}", parts[0])))
+        })
     }
 }
 
 const REG_SZ: usize = 4;
 
-struct ALU {
+struct Alu {
     registers: [isize; REG_SZ]
 }
 
-impl ALU {
+impl Alu {
     fn new() -> Self {
         Self {
             registers: [0; REG_SZ]
@@ -107,7 +130,7 @@ impl ALU {
                     return false;
                 }
             }
-            return self.registers[REG_SZ - 1] == 0
+            self.registers[REG_SZ - 1] == 0
         } else {
             false
         }
@@ -135,7 +158,7 @@ impl ALU {
                     self.registers[reg.get_index()] /= lit;
                     true
                 } else {
-                    eprintln!("attempt invalid div {:?}: {} /= {} - {:?}", op, self.registers[reg.get_index()], lit, self.registers);
+                    debug!("attempt invalid div {:?}: {} /= {} - {:?}", op, self.registers[reg.get_index()], lit, self.registers);
                     false
                 }
             }
@@ -145,7 +168,7 @@ impl ALU {
                     self.registers[reg.get_index()] %= lit;
                     true
                 } else {
-                    eprintln!("attempt invalid mod {:?}: {} %= {} - {:?}", op, self.registers[reg.get_index()], lit, self.registers);
+                    debug!("attempt invalid mod {:?}: {} %= {} - {:?}", op, self.registers[reg.get_index()], lit, self.registers);
                     false
                 }
             }
@@ -1,78 +1,56 @@
-use std::collections::HashSet;
-use array2d::Array2D;
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::grid::{Grid, Pos};
+use super::super::parse::Parser;
 
-type Loc = [usize; 2];
+const DAY: usize = 25;
+const EMPTY: char = '.';
+// each herd with the step it takes, east moves first
+const HERDS: [(char, (isize, isize)); 2] = [('>', (0, 1)), ('v', (1, 0))];
 
 pub struct Day25 {
-    herds: [HashSet<Loc>; 2],
-    width: usize,
-    height: usize
+    sea_floor: Grid<char>
 }
 
 impl Day25 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let mut east: HashSet<Loc> = HashSet::new();
-        let mut south: HashSet<Loc> = HashSet::new();
-        for (i, line) in content.lines().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                match c {
-                    '>' => east.insert([i, j]),
-                    'v' => south.insert([i, j]),
-                    _ => false
-                };
-            }
-        }
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
         Ok(Box::new(Day25 {
-            herds: [east, south],
-            width: content.lines().next().unwrap().len(),
-            height: content.lines().count()
+            sea_floor: Parser::new(DAY, content).char_grid(content, ">v.", "sea cucumber")?
         }))
     }
 
-    fn move_herd(&mut self, herd: usize) -> usize
-    {
-        let (max, incr) = if herd == 0 {
-            (self.width, 1)
-        } else {
-            (self.height, 0)
-        };
-        let moves: Vec<(Loc, Loc)> = self.herds[herd].iter()
-            .map(|curr| {
-                let mut next = curr.clone();
-                next[incr] = (next[incr] + 1) % max;
-                (*curr, next)
-            })
-            .filter(|(curr, next)| {
-                !self.herds.iter().any(|herd| herd.contains(next))
-            })
+    // the whole herd looks before any of it moves, off one edge is back in at the other
+    fn move_herd(sea_floor: &mut Grid<char>, herd: char, step: (isize, isize)) -> usize {
+        let moves: Vec<(Pos, Pos)> = sea_floor.iter()
+            .filter(|(_, cell)| **cell == herd)
+            .map(|(curr, _)| (curr, sea_floor.wrapping_offset(curr, step)))
+            .filter(|(_, next)| sea_floor[*next] == EMPTY)
             .collect();
-        let moves_len = moves.len();
-        for (before, after) in moves {
-            self.herds[herd].remove(&before);
-            self.herds[herd].insert(after);
+        for (before, after) in moves.iter() {
+            sea_floor[*before] = EMPTY;
+            sea_floor[*after] = herd;
         }
-        moves_len
+        moves.len()
     }
 }
 
 impl Day for Day25 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
+        let mut sea_floor = self.sea_floor.clone();
         let mut steps = 0;
         loop {
-            let mut moves = 0;
-            moves += self.move_herd(0);
-            moves += self.move_herd(1);
+            let moves: usize = HERDS.iter()
+                .map(|(herd, step)| Day25::move_herd(&mut sea_floor, *herd, *step))
+                .sum();
 
             steps += 1;
             if moves < 1 {
                 break;
             }
         }
-        steps as isize
+        Answer::Int(steps)
     }
 
-    fn part2(&mut self) -> isize {
-        0
+    fn part2(&self) -> Answer {
+        Answer::NotApplicable
     }
-}
\ No newline at end of file
+}
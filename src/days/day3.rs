@@ -1,23 +1,19 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use array2d::Array2D;
 
+const DAY: usize = 3;
+
 pub struct Day3 {
     values: Array2D<bool>
 }
 
 impl Day3 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let vecs: Vec<Vec<bool>> = content
-            .split_whitespace()
-            .map(|word| word
-                .chars()
-                .map(|char| {
-                    match char {
-                        '0' => false,
-                        '1' => true,
-                        _ => panic!("not binary")
-                    }})
-                .collect())
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let bits = parser.char_grid(content, "01", "bit")?;
+        let vecs: Vec<Vec<bool>> = (0..bits.rows())
+            .map(|row| (0..bits.cols()).map(|col| bits[(row, col)] == '1').collect())
             .collect();
 
         Ok(Box::new(Day3 {
@@ -28,7 +24,7 @@ impl Day3 {
 
 impl Day for Day3 {
 
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let counts = counts(&self.values);
     
         let gamma: usize = counts.iter()
@@ -40,14 +36,15 @@ impl Day for Day3 {
                 (epsilon << 1) + if count > self.values.column_len() / 2 {0} else {1}
             });
     
-        (gamma * epsilon).try_into().unwrap()
+        (gamma * epsilon).into()
     }
 
-    fn part2(&mut self) -> isize {
-        let ox = filter(&self.values, true).expect("unable to find O2");
-        let co2 = filter(&self.values, false).expect("unable to find CO2");
-
-        (ox * co2).try_into().unwrap()
+    fn part2(&self) -> Answer {
+        // some word lists whittle down to no word or to duplicates, those have no rating
+        match (filter(&self.values, true), filter(&self.values, false)) {
+            (Ok(ox), Ok(co2)) => (ox * co2).into(),
+            _ => Answer::NotApplicable
+        }
     }
 }
 
@@ -84,4 +81,19 @@ fn filter(vals: &Array2D<bool>, popular: bool) -> Result<usize, &'static str> {
             });
         Ok(dec)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::day::Answer;
+    use super::Day3;
+
+    #[test]
+    fn one_word_has_no_ratings() {
+        let day = Day3::from_content("10110\n").unwrap();
+        assert!(matches!(day.part1(), Answer::Int(198)));
+        assert!(matches!(day.part2(), Answer::NotApplicable));
+        let error = Day3::from_content("").err().unwrap();
+        assert_eq!((error.line, error.column, error.message), (1, 1, String::from("no rows")));
+    }
+}
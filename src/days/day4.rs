@@ -1,7 +1,9 @@
-use super::super::day::Day;
-use std::panic;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use multimap::MultiMap;
 
+const DAY: usize = 4;
+
 pub struct Day4 {
     nums: Vec<usize>,
     boards: Vec<Board>
@@ -15,7 +17,7 @@ struct Cell {
     hit: bool
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Board {
     cells: MultiMap<usize, Cell>,
     row_hits: Vec<usize>,
@@ -28,14 +30,13 @@ impl Board {
     pub fn from_row_major(rows: Vec<Vec<usize>>) -> Board {
         let mut cells: MultiMap<usize, Cell> = MultiMap::new();
         rows.iter().enumerate()
-            .map(|(i, row)| row.iter().enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate()
                 .map(move |(j, &num)| Cell{
                     row: i,
                     col: j,
-                    num: num,
+                    num,
                     hit: false
                 }))
-            .flatten()
             .for_each(|cell| cells.insert(cell.num, cell));
         Board {
             cells,
@@ -67,8 +68,7 @@ impl Board {
 
     pub fn unhit_sum(&self) -> usize {
         self.cells.iter_all()
-            .map(|(_, vec)| vec) // no iterator over all values :(
-            .flatten()
+            .flat_map(|(_, vec)| vec) // no iterator over all values :(
             .filter(|cell| !cell.hit)
             .map(|cell| cell.num)
             .sum()
@@ -80,33 +80,26 @@ impl Board {
 }
 
 impl Day4 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-
-        let mut iter = content.lines();
-        let conv_num = |n: &str| n.parse::<usize>().unwrap_or_else(|_| panic!("not a num: {}", n));
-        let nums: Vec<usize> = iter.next()
-            .expect("no first line")
-            .split(",")
-            .map(conv_num)
-            .collect();
-        let mut boards: Vec<Board> = Vec::new();
-        let mut next_board: Vec<Vec<usize>> = Vec::new();
-        for line in iter {
-            if line.len() < 1 && next_board.len() > 0 {
-                let b = Board::from_row_major(next_board);
-                boards.push(b);
-                next_board = Vec::new();
-            } else if line.len() > 0 {
-                let row: Vec<usize> = line.split_whitespace()
-                    .map(conv_num)
-                    .collect();
-                next_board.push(row);
-            }
-        }
-
-        if next_board.len() > 0 {
-            let b = Board::from_row_major(next_board);
-            boards.push(b);
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+
+        let parser = Parser::new(DAY, content);
+        let blocks = parser.blocks();
+        let (nums_block, board_blocks) = blocks.split_first()
+            .ok_or_else(|| parser.eof("no first line"))?;
+        let nums: Vec<usize> = parser.nums(nums_block, ',')?;
+        let boards: Vec<Board> = board_blocks.iter()
+            .map(|block| {
+                let rows: Vec<Vec<usize>> = block.lines()
+                    .map(|line| line.split_whitespace().map(|word| parser.num(word)).collect())
+                    .collect::<Result<_, _>>()?;
+                if let Some(line) = block.lines().find(|line| line.split_whitespace().count() != rows[0].len()) {
+                    return Err(parser.error(line, format!("expected {} numbers in board row", rows[0].len())));
+                }
+                Ok(Board::from_row_major(rows))
+            })
+            .collect::<Result<_, _>>()?;
+        if boards.is_empty() {
+            return Err(parser.eof("no boards"));
         }
 
         Ok(Box::new(Day4 {nums, boards}))
@@ -115,32 +108,60 @@ impl Day4 {
 
 impl Day for Day4 {
 
-    fn part1(&mut self) -> isize {
-        for n in self.nums.iter_mut() {
-            for b in self.boards.iter_mut() {
+    fn part1(&self) -> Answer {
+        let mut boards = self.boards.clone();
+        for n in self.nums.iter() {
+            for b in boards.iter_mut() {
                 if b.mark_all(*n) {
-                    return (*n * b.unhit_sum()).try_into().unwrap();
+                    return (*n * b.unhit_sum()).into();
                 }
             }
         }
 
-        0
+        Answer::NotApplicable
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let num_boards = self.boards.len();
         let mut completed = 0;
-        for n in self.nums.iter_mut() {
-            for b in self.boards.iter_mut() {
+        let mut boards = self.boards.clone();
+        for n in self.nums.iter() {
+            for b in boards.iter_mut() {
                 if !b.done() && b.mark_all(*n) {
                     completed += 1;
-                }
-                if completed + 1 == num_boards {
-                    return (*n * b.unhit_sum()).try_into().unwrap();         
+                    if completed == num_boards {
+                        return (*n * b.unhit_sum()).into();
+                    }
                 }
             }
         }
 
-        0
+        Answer::NotApplicable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day4;
+
+    fn error_at(content: &str) -> (usize, usize, String) {
+        let error = Day4::from_content(content).err().unwrap();
+        (error.line, error.column, error.message)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn bad_numbers() {
+        assert_eq!(error_at("7,4,x9\n\n22 13\n8 2\n"), (1, 5, String::from("not a num: x9")));
+        assert_eq!(error_at("7,4\n\n22 1x 17\n"), (3, 4, String::from("not a num: 1x")));
+    }
+
+    #[test]
+    fn ragged_board() {
+        assert_eq!(error_at("7,4\n\n22 13 17\n8 2\n"), (4, 1, String::from("expected 3 numbers in board row")));
+    }
+
+    #[test]
+    fn no_boards() {
+        assert_eq!(error_at("7,4\n"), (2, 1, String::from("no boards")));
+    }
+}
@@ -1,6 +1,8 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use std::cmp;
-use itertools::Itertools;
+
+const DAY: usize = 5;
 
 
 #[derive(Debug, Copy, Clone)]
@@ -22,14 +24,17 @@ pub struct Day5 {
 }
 
 impl Day5 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
-        let lines: Vec<Line> = content.split_whitespace()
-            .tuples()
-            .map(|(a, _, b)| Line {
-                    a: Point::from_comma_string(a),
-                    b: Point::from_comma_string(b),
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let lines: Vec<Line> = parser.lines()
+            .map(|line| {
+                let (a, b) = parser.pair(line, "->", "x1,y1 -> x2,y2")?;
+                Ok(Line {
+                    a: Point::from_comma_string(&parser, a)?,
+                    b: Point::from_comma_string(&parser, b)?,
                 })
-            .collect();
+            })
+            .collect::<Result<_, _>>()?;
         let (max_x, max_y) = lines.iter()
             .fold((0, 0), |(max_x, max_y), line| {
                 (cmp::max(max_x, cmp::max(line.a.x, line.b.x)),
@@ -45,7 +50,7 @@ impl Day5 {
 }
 
 impl Day for Day5 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let mut grid = vec![vec![0; self.max_y + 1]; self.max_x + 1];
         for line in self.lines.iter() {
             if line.a.x == line.b.x || line.a.y == line.b.y {
@@ -58,10 +63,11 @@ impl Day for Day5 {
         grid.iter()
             .flatten()
             .filter(|&&count| count > 1)
-            .count() as isize
+            .count()
+            .into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let mut grid = vec![vec![0; self.max_y + 1]; self.max_x + 1];
         for line in self.lines.iter() {
             for point in line {
@@ -72,20 +78,18 @@ impl Day for Day5 {
         grid.iter()
             .flatten()
             .filter(|&&count| count > 1)
-            .count() as isize
+            .count()
+            .into()
     }
 }
 
 impl Point {
-    fn from_comma_string(s: &str) -> Point {
-        s.splitn(2, ",")
-            .tuples()
-            .map(|(x, y)| Point {
-                    x: x.parse().expect("not a num"),
-                    y: y.parse().expect("not a num"),
-                })
-            .next()
-            .expect("no points")
+    fn from_comma_string<'a>(parser: &Parser<'a>, s: &'a str) -> Result<Point, ParseError> {
+        let (x, y) = parser.pair(s, ",", "x,y")?;
+        Ok(Point {
+            x: parser.num(x)?,
+            y: parser.num(y)?,
+        })
     }
 }
 
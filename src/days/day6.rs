@@ -1,4 +1,7 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
+
+const DAY: usize = 6;
 
 const SZ_STATES: usize = 9;
 const STATE_POSTPARTUM: usize = 6;
@@ -9,18 +12,21 @@ pub struct Day6 {
 }
 
 impl Day6 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let fishes: Vec<usize> = parser.list(content.trim(), ',', |word| match parser.num(word)? {
+            fish if fish >= SZ_STATES => Err(parser.error(word, format!("fish timers are below {}", SZ_STATES))),
+            fish => Ok(fish)
+        })?;
         Ok(Box::new(Day6 {
-            fishes: content.split(',')
-                .filter_map(|n| n.parse().ok())
-                .collect()
+            fishes
         }))
     }
 }
 
 impl Day for Day6 {
 
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let mut fishes = self.fishes.clone();
         for _ in 0..80 {
             let mut new_fish: Vec<usize> = Vec::new();
@@ -34,11 +40,11 @@ impl Day for Day6 {
             }
             fishes.append(&mut new_fish);
         }
-        fishes.len() as isize
+        fishes.len().into()
     }
 
-    fn part2(&mut self) -> isize {
-        let mut fish_states = [0 as usize; SZ_STATES];
+    fn part2(&self) -> Answer {
+        let mut fish_states = [0_usize; SZ_STATES];
         for fish in &self.fishes {
             fish_states[*fish] += 1;
         }
@@ -53,6 +59,6 @@ impl Day for Day6 {
             fish_states[STATES_NASCENT] += births;
         }
 
-        fish_states.iter().sum::<usize>() as isize
+        fish_states.iter().sum::<usize>().into()
     }
 }
\ No newline at end of file
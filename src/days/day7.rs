@@ -1,29 +1,26 @@
-use super::super::day::Day;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use std::cmp;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 
+const DAY: usize = 7;
+
 pub struct Day7 {
-    crab_hpos: Vec<usize>,
-    linear_costs: HashMap<usize, usize>
+    crab_hpos: Vec<usize>
 }
 
 impl Day7 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
         Ok(Box::new(Day7 {
-            crab_hpos: content
-                .trim()
-                .split(",")
-                .filter_map(|n| n.parse().ok())
-                .collect(),
-            linear_costs: HashMap::new()
+            crab_hpos: Parser::new(DAY, content).nums(content.trim(), ',')?
         }))
     }
 }
 
 impl Day for Day7 {
 
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         let min = self.crab_hpos.iter().min().unwrap();
         let max = self.crab_hpos.iter().max().unwrap();
         let mut min_cost = usize::MAX;
@@ -34,28 +31,29 @@ impl Day for Day7 {
             }
             min_cost = cmp::min(min_cost, cost as usize);
         }
-        min_cost as isize
+        min_cost.into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
         let crabs = self.crab_hpos.clone();
         let min = self.crab_hpos.iter().min().unwrap();
         let max = self.crab_hpos.iter().max().unwrap();
+        let mut linear_costs: HashMap<usize, usize> = HashMap::new();
         let mut min_cost = usize::MAX;
         for p in *min..*max {
             let mut cost: usize = 0;
             for crab in crabs.iter() {
-                cost += self.mv_cost((p as isize - *crab as isize).abs() as usize);
+                cost += Day7::mv_cost(&mut linear_costs, (p as isize - *crab as isize).unsigned_abs());
             }
             min_cost = cmp::min(min_cost, cost);
         }
-        min_cost as isize
+        min_cost.into()
     }
 }
 
 impl Day7 {
-    fn mv_cost(&mut self, mut dist: usize) -> usize {
-        match self.linear_costs.entry(dist) {
+    fn mv_cost(linear_costs: &mut HashMap<usize, usize>, mut dist: usize) -> usize {
+        match linear_costs.entry(dist) {
             Entry::Occupied(entry) => {
                 *entry.get()
             }
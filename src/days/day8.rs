@@ -1,7 +1,10 @@
-use super::super::day::Day;
-use itertools::Itertools;
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
 use bimap::BiHashMap;
 
+const DAY: usize = 8;
+const CYPHER_DIGITS: usize = 10;
+
 struct Pair {
     cypher: Vec<u8>,
     target: Vec<u8>
@@ -12,29 +15,32 @@ pub struct Day8 {
 }
 
 impl Day8 {
-    pub fn from_content(content: &str) -> Result<Box<dyn Day>, &'static str> {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
         Ok(Box::new(Day8 {
-            entries: content.lines()
-                .map(|line| line.splitn(2, '|')
-                    .tuples()
-                    .map(|(cypher_words, target_words)| Pair {
-                        cypher: cypher_words.split_whitespace().map(alpha_to_bits).collect(),
-                        target: target_words.split_whitespace().map(alpha_to_bits).collect()
+            entries: parser.lines()
+                .map(|line| {
+                    let (cypher_words, target_words) = parser.pair(line, "|", "<cypher digits> | <target digits>")?;
+                    let cypher: Vec<u8> = cypher_words.split_whitespace()
+                        .map(|word| alpha_to_bits(&parser, word))
+                        .collect::<Result<_, _>>()?;
+                    Ok(Pair {
+                        cypher: parser.expect_len(cypher, CYPHER_DIGITS, line, "cypher digits")?,
+                        target: target_words.split_whitespace()
+                            .map(|word| alpha_to_bits(&parser, word))
+                            .collect::<Result<_, _>>()?
                     })
-                    .collect::<Vec<Pair>>()
-                )
-                .flatten()
-                .collect()
+                })
+                .collect::<Result<_, _>>()?
         }))
     }
 }
 
 impl Day for Day8 {
-    fn part1(&mut self) -> isize {
+    fn part1(&self) -> Answer {
         self.entries.iter()
-            .map(|entry| &entry.target)
-            .flatten()
-            .fold(0isize, |count, word| {
+            .flat_map(|entry| &entry.target)
+            .fold(0usize, |count, word| {
                 count + match sum_bits(*word) {
                     2 => 1,
                     3 => 1,
@@ -43,9 +49,10 @@ impl Day for Day8 {
                     _ => 0
                 }
             })
+            .into()
     }
 
-    fn part2(&mut self) -> isize {
+    fn part2(&self) -> Answer {
 
         let mut sum = 0isize;
         for entry in self.entries.iter() {
@@ -81,50 +88,50 @@ impl Day for Day8 {
 
             // (4 | a) ^ [069] == 1 => 9,g
             let bits_4a = cypher_4 | cypher_bit_a;
-            set069 = set069.into_iter().filter(|cypher_digit| {
+            set069.retain(|cypher_digit| {
                 if sum_bits(bits_4a ^ *cypher_digit) == 1 {
                     plain_to_cypher.insert(9, *cypher_digit);
                     false
                 } else {true}
-            }).collect();
+            });
 
             // (1 ^ 4) & [06] == 1 => 0,d
             let bits_1x4 = cypher_1 ^ cypher_4;
-            set069 = set069.into_iter().filter(|cypher_digit| {
+            set069.retain(|cypher_digit| {
                     if sum_bits(cypher_digit & bits_1x4) == 1 {
                         plain_to_cypher.insert(0, *cypher_digit);
                         false
                     } else {true}
-                }).collect();
+                });
 
             // [6]
             if set069.len() == 1 {
-                plain_to_cypher.insert(6, *set069.get(0).unwrap());
+                plain_to_cypher.insert(6, *set069.first().unwrap());
             } else {
                 panic!("algo not working: [069]");
             }
 
             let cypher_6 = *plain_to_cypher.get_by_left(&6).unwrap();
             // 6 ^ [235] == 1 => 5,e
-            set235 = set235.into_iter().filter(|cypher_digit| {
+            set235.retain(|cypher_digit| {
                 if sum_bits(cypher_6 ^ *cypher_digit) == 1 {
                     plain_to_cypher.insert(5, *cypher_digit);
                     false
                 } else {true}
-            }).collect();
+            });
 
             let cypher_9 = *plain_to_cypher.get_by_left(&9).unwrap();
             // 9 ^ [23] == 1 => 5,e
-            set235 = set235.into_iter().filter(|cypher_digit| {
+            set235.retain(|cypher_digit| {
                 if sum_bits(cypher_9 ^ *cypher_digit) == 1 {
                     plain_to_cypher.insert(3, *cypher_digit);
                     false
                 } else {true}
-            }).collect();
+            });
 
             // [2]
             if set235.len() == 1 {
-                plain_to_cypher.insert(2, *set235.get(0).unwrap());
+                plain_to_cypher.insert(2, *set235.first().unwrap());
             } else {
                 panic!("algo not working: [235]");
             }
@@ -143,7 +150,7 @@ impl Day for Day8 {
             sum += plain_num;
         }
 
-        sum
+        Answer::Int(sum)
     }
 }
 
@@ -151,13 +158,13 @@ fn sum_bits(mut word: u8) -> usize {
     let mut sum = 0usize;
     for _ in 0..8 {
         sum += (word & 1) as usize;
-        word = word >> 1;
+        word >>= 1;
     }
     sum
 }
 
-fn alpha_to_bits(word: &str) -> u8 {
-    word.chars().fold(0u8, |bits, c| bits | match c {
+fn alpha_to_bits(parser: &Parser, word: &str) -> Result<u8, ParseError> {
+    word.char_indices().try_fold(0u8, |bits, (i, c)| Ok(bits | match c {
             'a' => 1 << 0,
             'b' => 1 << 1,
             'c' => 1 << 2,
@@ -165,6 +172,6 @@ fn alpha_to_bits(word: &str) -> u8 {
             'e' => 1 << 4,
             'f' => 1 << 5,
             'g' => 1 << 6,
-            _ => panic!("unsupported character {}", c)
-        })
+            _ => return Err(parser.error(&word[i..], format!("unsupported character {}", c)))
+        }))
 }
\ No newline at end of file
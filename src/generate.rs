@@ -0,0 +1,674 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use itertools::Itertools;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use super::geometry::{Cuboid, Point3, Rotation};
+
+type Generator = fn(&mut StdRng, usize) -> String;
+
+// day, the sizes that still make a valid input, and its generator. what size counts is up to each day
+const GENERATORS: [(usize, RangeInclusive<usize>, Generator); 23] = [
+    (1, 3..=usize::MAX, day1),
+    (2, 1..=usize::MAX, day2),
+    (3, 2..=usize::MAX, day3),
+    (4, 1..=usize::MAX, day4),
+    (5, 1..=usize::MAX, day5),
+    (6, 1..=usize::MAX, day6),
+    (7, 1..=usize::MAX, day7),
+    (8, 1..=usize::MAX, day8),
+    (9, 9..=usize::MAX, day9),
+    (10, 1..=usize::MAX, day10),
+    (11, 1..=usize::MAX, day11),
+    (12, 2..=20, day12),
+    (13, 1..=usize::MAX, day13),
+    (14, 2..=usize::MAX, day14),
+    (15, 2..=usize::MAX, day15),
+    (16, 1..=usize::MAX, day16),
+    (17, 10..=600, day17),
+    (18, 2..=usize::MAX, day18),
+    (19, 2..=usize::MAX, day19),
+    (20, 1..=usize::MAX, day20),
+    (21, 0..=usize::MAX, day21),
+    (22, 1..=usize::MAX, day22),
+    (23, 2..=usize::MAX, day23)
+];
+
+// the days no random input can be made for, and why
+const UNGENERATED: [(usize, &str); 2] = [
+    (24, "its solver only checks model numbers worked out by hand from one particular monad program"),
+    (25, "random sea cucumbers need not ever stop moving, and the day has no answer until they do")
+];
+
+// the days a random input can be made for
+pub fn days() -> Vec<usize> {
+    GENERATORS.iter().map(|(day, _, _)| *day).collect()
+}
+
+// the same day, size and seed always give the same input
+pub fn generate(day: usize, size: usize, seed: u64) -> Result<String, String> {
+    let (_, sizes, generator) = GENERATORS.iter()
+        .find(|(generator_day, _, _)| *generator_day == day)
+        .ok_or_else(|| match UNGENERATED.iter().find(|(ungenerated_day, _)| *ungenerated_day == day) {
+            Some((_, reason)) => format!("no generator for day {}, {}", day, reason),
+            None => format!("no generator for day {}, there are generators for days {}", day, days().iter().join(", "))
+        })?;
+    if !sizes.contains(&size) {
+        return Err(match sizes.end() {
+            &usize::MAX => format!("day {} needs a size of at least {}", day, sizes.start()),
+            end => format!("day {} needs a size from {} to {}", day, sizes.start(), end)
+        });
+    }
+    Ok(generator(&mut StdRng::seed_from_u64(seed), size))
+}
+
+// size depths, wandering mostly deeper. part 2 needs one full window of three
+fn day1(rng: &mut StdRng, size: usize) -> String {
+    let mut depth: isize = rng.gen_range(100..200);
+    (0..size)
+        .map(|_| {
+            depth = (depth + rng.gen_range(-10..=20)).max(0);
+            format!("{}\n", depth)
+        })
+        .collect()
+}
+
+// size commands
+fn day2(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{} {}\n", ["forward", "down", "up"].choose(rng).unwrap(), rng.gen_range(1..10)))
+        .collect()
+}
+
+// size different numbers, as wide as they need to be and at least 5 bits. drawn again until both ratings whittle
+// down to one number, which they don't when the few left all share their next bit
+fn day3(rng: &mut StdRng, size: usize) -> String {
+    fn rating_found(nums: &[usize], width: usize, most_common: bool) -> bool {
+        let mut matches = nums.to_vec();
+        for bit in (0..width).rev() {
+            let ones = matches.iter().filter(|num| *num >> bit & 1 == 1).count();
+            let keep = (ones * 2 >= matches.len()) == most_common;
+            matches.retain(|num| (num >> bit & 1 == 1) == keep);
+            if matches.len() < 2 {
+                break;
+            }
+        }
+        matches.len() == 1
+    }
+
+    let width = (usize::BITS - (size - 1).leading_zeros()).max(5) as usize;
+    loop {
+        let nums = rand::seq::index::sample(rng, 1 << width, size).into_vec();
+        if rating_found(&nums, width, true) && rating_found(&nums, width, false) {
+            return nums.iter()
+                .map(|num| format!("{:0width$b}\n", num, width = width))
+                .collect();
+        }
+    }
+}
+
+// size boards. every number a board holds is drawn, so every board wins
+fn day4(rng: &mut StdRng, size: usize) -> String {
+    let mut pool: Vec<usize> = (0..100.max(size * 2)).collect();
+    pool.shuffle(rng);
+    let mut content = format!("{}\n", pool.iter().join(","));
+    for _ in 0..size {
+        let board: Vec<&usize> = pool.choose_multiple(rng, 25).collect();
+        content.push('\n');
+        for row in board.chunks(5) {
+            writeln!(content, "{}", row.iter().map(|num| format!("{:>2}", num)).join(" ")).unwrap();
+        }
+    }
+    content
+}
+
+// size lines, each horizontal, vertical or diagonal and never a single point
+fn day5(rng: &mut StdRng, size: usize) -> String {
+    const DIRECTIONS: [(isize, isize); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+    let mut content = String::new();
+    let mut lines = 0;
+    while lines < size {
+        let (x1, y1) = (rng.gen_range(0..1000isize), rng.gen_range(0..1000isize));
+        let (dx, dy) = *DIRECTIONS.choose(rng).unwrap();
+        // how far it can go before leaving the 1000 by 1000 floor
+        let room = [(dx, x1), (dy, y1)].iter()
+            .filter(|(d, _)| *d != 0)
+            .map(|(d, coord)| if *d > 0 { 999 - coord } else { *coord })
+            .min()
+            .unwrap();
+        if room > 0 {
+            let len = rng.gen_range(1..=room.min(300));
+            writeln!(content, "{},{} -> {},{}", x1, y1, x1 + dx * len, y1 + dy * len).unwrap();
+            lines += 1;
+        }
+    }
+    content
+}
+
+// size lanternfish
+fn day6(rng: &mut StdRng, size: usize) -> String {
+    format!("{}\n", (0..size).map(|_| rng.gen_range(1..=5)).join(","))
+}
+
+// size crabs
+fn day7(rng: &mut StdRng, size: usize) -> String {
+    format!("{}\n", (0..size).map(|_| rng.gen_range(0..2000)).join(","))
+}
+
+// size displays, each wired up its own way
+fn day8(rng: &mut StdRng, size: usize) -> String {
+    const DIGITS: [&str; 10] = ["abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg"];
+    let mut content = String::new();
+    for _ in 0..size {
+        let mut wires: Vec<char> = "abcdefg".chars().collect();
+        wires.shuffle(rng);
+        let wired = |rng: &mut StdRng, digit: &str| {
+            let mut segments: Vec<char> = digit.chars().map(|c| wires[c as usize - 'a' as usize]).collect();
+            segments.shuffle(rng);
+            segments.into_iter().collect::<String>()
+        };
+        let mut patterns: Vec<String> = DIGITS.iter().map(|digit| wired(rng, digit)).collect();
+        let outputs: Vec<String> = (0..4)
+            .map(|_| {
+                let digit = DIGITS[rng.gen_range(0..10)];
+                wired(rng, digit)
+            })
+            .collect();
+        patterns.shuffle(rng);
+        writeln!(content, "{} | {}", patterns.join(" "), outputs.join(" ")).unwrap();
+    }
+    content
+}
+
+// a size by size heightmap. every fourth column is a wall of 9s and the top of each strip between them is low,
+// so there are always three basins at least
+fn day9(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|row| {
+            let line: String = (0..size)
+                .map(|col| match (row, col % 4) {
+                    (_, 3) => 9,
+                    (0, 0) => rng.gen_range(0..9),
+                    _ if rng.gen_bool(0.25) => 9,
+                    _ => rng.gen_range(0..9)
+                })
+                .join("");
+            format!("{}\n", line)
+        })
+        .collect()
+}
+
+// size lines, each corrupted or incomplete. there is always an odd count of incomplete ones so there is a middle score.
+// a completion score grows fivefold with every chunk left open, so no more than 20 are open at once
+fn day10(rng: &mut StdRng, size: usize) -> String {
+    const CHUNKS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+    const MAX_OPEN: usize = 20;
+    let mut incomplete: Vec<bool> = (0..size).map(|_| rng.gen_bool(0.5)).collect();
+    if incomplete.iter().filter(|line| **line).count() % 2 == 0 {
+        incomplete[size - 1] = !incomplete[size - 1];
+    }
+    let mut content = String::new();
+    for line_incomplete in incomplete {
+        let mut line = String::new();
+        let mut open: Vec<(char, char)> = Vec::new();
+        for _ in 0..rng.gen_range(10..110) {
+            if open.len() >= MAX_OPEN || (!open.is_empty() && rng.gen_bool(0.4)) {
+                line.push(open.pop().unwrap().1);
+            } else {
+                let chunk = *CHUNKS.choose(rng).unwrap();
+                line.push(chunk.0);
+                open.push(chunk);
+            }
+        }
+        if open.is_empty() {
+            line.push(CHUNKS[0].0);
+            open.push(CHUNKS[0]);
+        }
+        if !line_incomplete {
+            let expected = open.last().unwrap().1;
+            line.push(CHUNKS.iter().map(|(_, close)| *close).filter(|close| *close != expected).collect::<Vec<_>>()
+                .choose(rng).copied().unwrap());
+        }
+        writeln!(content, "{}", line).unwrap();
+    }
+    content
+}
+
+// a size by size grid of octopuses. they need not ever flash together, part 2 has no answer then
+fn day11(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{}\n", (0..size).map(|_| rng.gen_range(0..10)).join("")))
+        .collect()
+}
+
+// size small caves and a big one for every three of them. big caves only ever lead to small ones, or the paths
+// would never end, and a small cave always links start to end. the paths grow so fast that past 20 caves
+// counting them takes minutes
+fn day12(rng: &mut StdRng, size: usize) -> String {
+    let mut names: HashSet<String> = HashSet::new();
+    let mut name = |rng: &mut StdRng, letters: RangeInclusive<char>| loop {
+        let candidate: String = (0..2).map(|_| rng.gen_range(letters.clone())).collect();
+        if names.insert(candidate.clone()) {
+            break candidate;
+        }
+    };
+    let small: Vec<String> = (0..size).map(|_| name(rng, 'a'..='z')).collect();
+    let big: Vec<String> = (0..size / 3).map(|_| name(rng, 'A'..='Z')).collect();
+
+    // either way round is the same tunnel
+    let mut edges: HashSet<(String, String)> = HashSet::new();
+    let mut link = |a: &str, b: &str| if a != b {
+        edges.insert(if a < b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) });
+    };
+    link("start", &small[0]);
+    link(&small[0], "end");
+    for cave in small.iter() {
+        for _ in 0..rng.gen_range(1..=2) {
+            link(cave, small.choose(rng).unwrap());
+        }
+    }
+    for cave in big.iter() {
+        for _ in 0..rng.gen_range(2..=4) {
+            link(cave, small.choose(rng).unwrap());
+        }
+    }
+    for _ in 0..rng.gen_range(1..=2) {
+        link("start", small.choose(rng).unwrap());
+        link(small.choose(rng).unwrap(), "end");
+    }
+    edges.into_iter()
+        .sorted()
+        .map(|(from, to)| format!("{}-{}\n", from, to))
+        .collect()
+}
+
+// size dots, folded a dozen times down to a 39 by 6 code. each dot starts somewhere on the code and is unfolded out,
+// so none lands on a fold
+fn day13(rng: &mut StdRng, size: usize) -> String {
+    let (mut width, mut height) = (39, 6);
+    let mut folds = Vec::new();
+    for i in 0..12 {
+        if i % 2 == 0 {
+            folds.push(('x', width));
+            width = width * 2 + 1;
+        } else {
+            folds.push(('y', height));
+            height = height * 2 + 1;
+        }
+    }
+    let dots: HashSet<(usize, usize)> = (0..size)
+        .map(|_| {
+            let mut dot = (rng.gen_range(0..39), rng.gen_range(0..6));
+            for (axis, at) in folds.iter() {
+                if rng.gen_bool(0.5) {
+                    match axis {
+                        'x' => dot.0 = at * 2 - dot.0,
+                        _ => dot.1 = at * 2 - dot.1
+                    }
+                }
+            }
+            dot
+        })
+        .collect();
+    let mut content: String = dots.into_iter()
+        .sorted()
+        .map(|(x, y)| format!("{},{}\n", x, y))
+        .collect();
+    content.push('\n');
+    for (axis, at) in folds.iter().rev() {
+        writeln!(content, "fold along {}={}", axis, at).unwrap();
+    }
+    content
+}
+
+// a template size elements long, with an insertion for every pair of its ten elements
+fn day14(rng: &mut StdRng, size: usize) -> String {
+    let mut letters: Vec<char> = ('A'..='Z').collect();
+    letters.shuffle(rng);
+    let elements = &letters[..10];
+    let mut content: String = (0..size).map(|_| *elements.choose(rng).unwrap()).collect();
+    content.push_str("\n\n");
+    for (a, b) in elements.iter().cartesian_product(elements.iter()) {
+        writeln!(content, "{}{} -> {}", a, b, elements.choose(rng).unwrap()).unwrap();
+    }
+    content
+}
+
+// a size by size risk map
+fn day15(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{}\n", (0..size).map(|_| rng.gen_range(1..=9)).join("")))
+        .collect()
+}
+
+// a transmission of about size packets
+fn day16(rng: &mut StdRng, size: usize) -> String {
+    enum Packet {
+        Literal(u64, u64),
+        Operator(u64, u64, Vec<Packet>)
+    }
+
+    fn push_bits(bits: &mut Vec<bool>, value: u64, width: usize) {
+        bits.extend((0..width).rev().map(|bit| value >> bit & 1 == 1));
+    }
+
+    // products only take small literals and comparisons so nothing overflows
+    fn packet(rng: &mut StdRng, budget: &mut usize, small: bool) -> Packet {
+        *budget = budget.saturating_sub(1);
+        let version = rng.gen_range(0..8);
+        if *budget == 0 || rng.gen_bool(if small { 0.6 } else { 0.3 }) {
+            return Packet::Literal(version, rng.gen_range(0..if small { 1 << 8 } else { 1 << 16 }));
+        }
+        let type_id = if small { rng.gen_range(5..8) } else { *[0, 1, 2, 3, 5, 6, 7].choose(rng).unwrap() };
+        let children = match type_id {
+            0 | 2 | 3 => (0..rng.gen_range(1..=4)).map(|_| packet(rng, budget, false)).collect(),
+            1 => (0..rng.gen_range(1..=3)).map(|_| packet(rng, budget, true)).collect(),
+            _ => (0..2).map(|_| packet(rng, budget, false)).collect()
+        };
+        Packet::Operator(version, type_id, children)
+    }
+
+    fn encode(rng: &mut StdRng, packet: &Packet, bits: &mut Vec<bool>) {
+        match packet {
+            Packet::Literal(version, value) => {
+                push_bits(bits, *version, 3);
+                push_bits(bits, 4, 3);
+                let groups = ((u64::BITS - value.leading_zeros()) as usize).div_ceil(4);
+                for group in (0..groups.max(1)).rev() {
+                    bits.push(group > 0);
+                    push_bits(bits, value >> (group * 4) & 0xf, 4);
+                }
+            }
+            Packet::Operator(version, type_id, children) => {
+                push_bits(bits, *version, 3);
+                push_bits(bits, *type_id, 3);
+                let mut child_bits = Vec::new();
+                children.iter().for_each(|child| encode(rng, child, &mut child_bits));
+                if child_bits.len() < 1 << 15 && rng.gen_bool(0.5) {
+                    bits.push(false);
+                    push_bits(bits, child_bits.len() as u64, 15);
+                } else {
+                    bits.push(true);
+                    push_bits(bits, children.len() as u64, 11);
+                }
+                bits.extend(child_bits);
+            }
+        }
+    }
+
+    // the outermost sum keeps taking packets until the budget is spent, so the size is about right
+    let mut budget = size;
+    let mut children = vec![packet(rng, &mut budget, false)];
+    while budget > 0 && children.len() < 1 << 11 {
+        children.push(packet(rng, &mut budget, false));
+    }
+    let mut bits = Vec::new();
+    let outermost = Packet::Operator(rng.gen_range(0..8), 0, children);
+    encode(rng, &outermost, &mut bits);
+    while bits.len() % 8 != 0 {
+        bits.push(false);
+    }
+    let hex: String = bits.chunks(4)
+        .map(|nibble| format!("{:X}", nibble.iter().fold(0, |value, bit| value << 1 | *bit as u8)))
+        .collect();
+    format!("{}\n", hex)
+}
+
+// a target about size to the right and below, wide enough that some probe stops dead above it
+fn day17(rng: &mut StdRng, size: usize) -> String {
+    let x_min = rng.gen_range(size..=size * 2);
+    let x_max = x_min + rng.gen_range(((size * 8) as f64).sqrt() as usize..=size);
+    let y_max = -(rng.gen_range(1..=size) as isize);
+    let y_min = y_max - rng.gen_range(1..=size / 2) as isize;
+    format!("target area: x={}..{}, y={}..{}\n", x_min, x_max, y_min, y_max)
+}
+
+// size snailfish numbers, none nested deeper than four pairs
+fn day18(rng: &mut StdRng, size: usize) -> String {
+    fn number(rng: &mut StdRng, depth: usize) -> String {
+        if depth == 4 || (depth > 0 && rng.gen_bool(0.35)) {
+            rng.gen_range(0..10).to_string()
+        } else {
+            format!("[{},{}]", number(rng, depth + 1), number(rng, depth + 1))
+        }
+    }
+    (0..size)
+        .map(|_| format!("{}\n", number(rng, 0)))
+        .collect()
+}
+
+// size scanners, each reporting the beacons within 1000 of it turned its own way. every scanner after the first
+// is placed a little over its range from one before it, clear of the rest, and the two are given a dozen beacons
+// in common so they all piece together
+fn day19(rng: &mut StdRng, size: usize) -> String {
+    scanner_field(rng, size).content
+}
+
+// where day 19's generator put everything, which is what the day should work out again. only the tests look
+#[cfg_attr(not(test), allow(dead_code))]
+struct ScannerField {
+    scanners: Vec<Point3>,
+    beacons: Vec<Point3>,
+    content: String
+}
+
+fn scanner_field(rng: &mut StdRng, size: usize) -> ScannerField {
+    const RANGE: isize = 1000;
+    let within = |scanner: &Point3| Cuboid::new(*scanner - Point3::new(RANGE, RANGE, RANGE),
+                                                *scanner + Point3::new(RANGE, RANGE, RANGE)).unwrap();
+    let inside = |rng: &mut StdRng, cuboid: &Cuboid| Point3::new(rng.gen_range(cuboid.min.x..=cuboid.max.x),
+                                                                 rng.gen_range(cuboid.min.y..=cuboid.max.y),
+                                                                 rng.gen_range(cuboid.min.z..=cuboid.max.z));
+
+    let mut scanners = vec![Point3::ORIGIN];
+    let mut links = vec![0];
+    let mut beacons: HashSet<Point3> = HashSet::new();
+    while scanners.len() < size {
+        // up to three neighbors each, much like a real input
+        let neighbor_idx = *(0..scanners.len()).filter(|idx| links[*idx] < 3).collect::<Vec<_>>().choose(rng).unwrap();
+        let neighbor = scanners[neighbor_idx];
+        let scanner = neighbor + inside(rng, &Cuboid::new(Point3::new(-1300, -1300, -1300), Point3::new(1300, 1300, 1300)).unwrap());
+        // seeing only into its neighbor's range keeps each beacon in view of few scanners, as in a real input
+        if within(&neighbor).contains(&scanner)
+            || scanners.iter().any(|other| *other != neighbor && within(other).intersection(&within(&scanner)).is_some()) {
+            continue;
+        }
+        let overlap = within(&scanner).intersection(&within(&neighbor)).unwrap();
+        let mut shared = 0;
+        while shared < 12 {
+            if beacons.insert(inside(rng, &overlap)) {
+                shared += 1;
+            }
+        }
+        scanners.push(scanner);
+        links[neighbor_idx] += 1;
+        links.push(1);
+    }
+    for scanner in scanners.iter() {
+        for _ in 0..rng.gen_range(0..6) {
+            beacons.insert(inside(rng, &within(scanner)));
+        }
+    }
+
+    let rotations = Rotation::all();
+    let beacons: Vec<Point3> = beacons.into_iter().sorted().collect();
+    let mut content = String::new();
+    for (idx, scanner) in scanners.iter().enumerate() {
+        let rotation = if idx == 0 { Rotation::IDENTITY } else { *rotations.choose(rng).unwrap() };
+        let mut seen: Vec<Point3> = beacons.iter()
+            .filter(|beacon| within(scanner).contains(beacon))
+            .map(|beacon| rotation.apply(&(*beacon - *scanner)))
+            .collect();
+        seen.shuffle(rng);
+        if idx > 0 {
+            content.push('\n');
+        }
+        writeln!(content, "--- scanner {} ---", idx).unwrap();
+        seen.iter().for_each(|beacon| writeln!(content, "{},{},{}", beacon.x, beacon.y, beacon.z).unwrap());
+    }
+    ScannerField {
+        scanners,
+        beacons,
+        content
+    }
+}
+
+// a size by size image. when nothing lit lights up the dark background, everything lit goes dark again,
+// or the lit pixels would never be finite
+fn day20(rng: &mut StdRng, size: usize) -> String {
+    let pixel = |rng: &mut StdRng| if rng.gen_bool(0.5) { '#' } else { '.' };
+    let mut enhancer: Vec<char> = (0..512).map(|_| pixel(rng)).collect();
+    if enhancer[0] == '#' {
+        enhancer[511] = '.';
+    }
+    let mut content: String = enhancer.into_iter().collect();
+    content.push_str("\n\n");
+    for _ in 0..size {
+        let row: String = (0..size).map(|_| pixel(rng)).collect();
+        writeln!(content, "{}", row).unwrap();
+    }
+    content
+}
+
+// size is unused, the board is always 10 spaces
+fn day21(rng: &mut StdRng, _size: usize) -> String {
+    (1..=2)
+        .map(|player| format!("Player {} starting position: {}\n", player, rng.gen_range(1..=10)))
+        .collect()
+}
+
+// size steps, the first half within 50 of the origin and the rest spread far out, starting with one that is on
+fn day22(rng: &mut StdRng, size: usize) -> String {
+    let range = |rng: &mut StdRng, reach: isize| {
+        let start = rng.gen_range(-reach..reach);
+        format!("{}..{}", start, (start + rng.gen_range(0..=reach / 2)).min(reach))
+    };
+    (0..size)
+        .map(|idx| {
+            let reach = if idx * 2 < size { 50 } else { 100000 };
+            let state = if idx == 0 || rng.gen_bool(0.5) { "on" } else { "off" };
+            format!("{} x={},y={},z={}\n", state, range(rng, reach), range(rng, reach), range(rng, reach))
+        })
+        .collect()
+}
+
+// rooms size amphipods deep, size of each kind shuffled between them. shuffled again while every room holds
+// one kind only, which no real input starts with
+fn day23(rng: &mut StdRng, size: usize) -> String {
+    let mut pods: Vec<char> = "ABCD".chars().flat_map(|pod| vec![pod; size]).collect();
+    loop {
+        pods.shuffle(rng);
+        if !(0..4).all(|room| pods.iter().skip(room).step_by(4).all_equal()) {
+            break;
+        }
+    }
+    let mut content = String::from("#############\n#...........#\n");
+    for (depth, row) in pods.chunks(4).enumerate() {
+        let (edge, wall) = if depth == 0 { ("###", "###") } else { ("  #", "#") };
+        writeln!(content, "{}{}{}", edge, row.iter().join("#"), wall).unwrap();
+    }
+    content.push_str("  #########\n");
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+    use itertools::Itertools;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::super::day::Answer;
+    use super::super::registry::{Registry, DEFAULT_YEAR};
+    use super::{generate, scanner_field, GENERATORS};
+
+    fn solve(day: usize, content: &str) -> (Answer, Answer) {
+        let solver = (Registry::new().get(DEFAULT_YEAR, day).unwrap().maker)(content).unwrap();
+        (solver.part1(), solver.part2())
+    }
+
+    fn int(answer: Answer) -> isize {
+        match answer {
+            Answer::Int(value) => value,
+            other => panic!("expected a number, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn same_seed_same_input() {
+        for day in super::days() {
+            let size = *GENERATORS.iter().find(|(generator_day, _, _)| *generator_day == day).unwrap().1.start();
+            assert_eq!(generate(day, size, 7), generate(day, size, 7), "day {}", day);
+        }
+    }
+
+    #[test]
+    fn sizes_outside_the_range_are_refused() {
+        assert_eq!(generate(1, 2, 0), Err(String::from("day 1 needs a size of at least 3")));
+        assert_eq!(generate(12, 21, 0), Err(String::from("day 12 needs a size from 2 to 20")));
+        assert_eq!(generate(24, 10, 0), Err(String::from("no generator for day 24, its solver only checks model numbers \
+                                                          worked out by hand from one particular monad program")));
+        assert!(generate(26, 10, 0).unwrap_err().starts_with("no generator for day 26, there are generators for days 1, 2"));
+    }
+
+    // the smallest sizes are where a generator is most likely to make something its day can't take
+    #[test]
+    fn every_generated_input_parses_and_solves() {
+        let registry = Registry::new();
+        for (day, sizes, _) in GENERATORS.iter() {
+            let registered = registry.get(DEFAULT_YEAR, *day).unwrap();
+            let small = [*sizes.start(), sizes.start() + 1, sizes.start() + 2];
+            for size in small.into_iter().filter(|size| sizes.contains(size)) {
+                for seed in 0..3 {
+                    let content = generate(*day, size, seed).unwrap();
+                    let solver = (registered.maker)(&content)
+                        .unwrap_or_else(|error| panic!("day {} size {} seed {}: {}", day, size, seed, error));
+                    let solved = panic::catch_unwind(AssertUnwindSafe(|| (solver.part1(), solver.part2())));
+                    let (part1, part2) = solved.unwrap_or_else(|_| panic!("day {} size {} seed {} panicked", day, size, seed));
+                    // every generator makes sure its day has answers, apart from day 11's octopuses ever syncing.
+                    // day 23 has no part 2 at all
+                    assert!(!matches!(part1, Answer::NotApplicable), "day {} size {} seed {} part 1", day, size, seed);
+                    assert!([11, 23].contains(day) || !matches!(part2, Answer::NotApplicable),
+                            "day {} size {} seed {} part 2", day, size, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn day1_counts_the_generated_increases() {
+        for seed in 0..5 {
+            let content = generate(1, 50, seed).unwrap();
+            let depths: Vec<isize> = content.lines().map(|line| line.parse().unwrap()).collect();
+            let increases = |gap: usize| depths.iter().zip(depths.iter().skip(gap)).filter(|(a, b)| b > a).count() as isize;
+            let (part1, part2) = solve(1, &content);
+            // the windows of three share two depths, so one is deeper when the depth it gains beats the one it loses
+            assert_eq!((int(part1), int(part2)), (increases(1), increases(3)), "seed {}", seed);
+        }
+    }
+
+    // a probe that stops dead above the target can be thrown as high as the target is deep, less one step
+    #[test]
+    fn day17_reaches_the_height_the_target_depth_allows() {
+        for seed in 0..5 {
+            let content = generate(17, 40, seed).unwrap();
+            let y_min: isize = content.split("y=").nth(1).unwrap().split("..").next().unwrap().parse().unwrap();
+            assert_eq!(int(solve(17, &content).0), y_min * (y_min + 1) / 2, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn day19_finds_the_placed_beacons_and_scanners() {
+        for seed in 0..3 {
+            let field = scanner_field(&mut StdRng::seed_from_u64(seed), 4);
+            let farthest = field.scanners.iter()
+                .tuple_combinations()
+                .map(|(a, b)| a.manhattan(b))
+                .max()
+                .unwrap();
+            let (part1, part2) = solve(19, &field.content);
+            assert_eq!((int(part1), int(part2)), (field.beacons.len() as isize, farthest), "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::cmp::{max, min};
+use std::ops::{Add, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: isize, y: isize, z: isize) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    pub fn from_coords([x, y, z]: [isize; 3]) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    pub fn coords(&self) -> [isize; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn manhattan(&self, other: &Point3) -> isize {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+// a quarter turn rotation as a matrix, each row a single 1 or -1 picking and flipping an axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    rows: [[isize; 3]; 3]
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation { rows: [[1, 0, 0], [0, 1, 0], [0, 0, 1]] };
+
+    // the 24 ways to face a cube: every signed permutation of the axes that is not a mirror image, identity first
+    pub fn all() -> Vec<Rotation> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let mut rotations = Vec::new();
+        for axes in PERMUTATIONS {
+            for signs in 0..8 {
+                let mut rows = [[0; 3]; 3];
+                for (row, axis) in axes.iter().enumerate() {
+                    rows[row][*axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                let rotation = Rotation { rows };
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+        rotations
+    }
+
+    fn determinant(&self) -> isize {
+        let m = &self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, point: &Point3) -> Point3 {
+        let coords = point.coords();
+        let row = |row: &[isize; 3]| row.iter().zip(coords.iter()).map(|(m, c)| m * c).sum();
+        Point3::new(row(&self.rows[0]), row(&self.rows[1]), row(&self.rows[2]))
+    }
+
+    // this rotation and then next
+    pub fn then(&self, next: &Rotation) -> Rotation {
+        let mut rows = [[0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| next.rows[i][k] * self.rows[k][j]).sum();
+            }
+        }
+        Rotation { rows }
+    }
+
+    // a rotation matrix is undone by its transpose
+    pub fn inverse(&self) -> Rotation {
+        let mut rows = [[0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = self.rows[j][i];
+            }
+        }
+        Rotation { rows }
+    }
+}
+
+// an axis aligned box of whole cells, both corners included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: Point3,
+    pub max: Point3
+}
+
+impl Cuboid {
+    // None when min is past max on any axis
+    pub fn new(min: Point3, max: Point3) -> Option<Cuboid> {
+        if min.x <= max.x && min.y <= max.y && min.z <= max.z {
+            Some(Cuboid { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn volume(&self) -> isize {
+        (self.max.x - self.min.x + 1) * (self.max.y - self.min.y + 1) * (self.max.z - self.min.z + 1)
+    }
+
+    pub fn contains(&self, point: &Point3) -> bool {
+        self.min.x <= point.x && point.x <= self.max.x
+            && self.min.y <= point.y && point.y <= self.max.y
+            && self.min.z <= point.z && point.z <= self.max.z
+    }
+
+    pub fn within(&self, other: &Cuboid) -> bool {
+        other.contains(&self.min) && other.contains(&self.max)
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        Cuboid::new(Point3::new(max(self.min.x, other.min.x), max(self.min.y, other.min.y), max(self.min.z, other.min.z)),
+                    Point3::new(min(self.max.x, other.max.x), min(self.max.y, other.max.y), min(self.max.z, other.max.z)))
+    }
+
+    // what is left of this box once other is cut out of it, as at most six boxes that don't overlap:
+    // the slabs left and right of the cut on x, then below and above it on y within those, then behind and in front on z
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let cut = match self.intersection(other) {
+            None => return vec![*self],
+            Some(cut) => cut
+        };
+        let mut rest = *self;
+        let mut pieces = Vec::new();
+        for axis in 0..3 {
+            let (rest_min, rest_max) = (rest.min.coords(), rest.max.coords());
+            let (cut_min, cut_max) = (cut.min.coords(), cut.max.coords());
+            let mut below_max = rest_max;
+            below_max[axis] = cut_min[axis] - 1;
+            let mut above_min = rest_min;
+            above_min[axis] = cut_max[axis] + 1;
+            pieces.extend(Cuboid::new(rest.min, Point3::from_coords(below_max)));
+            pieces.extend(Cuboid::new(Point3::from_coords(above_min), rest.max));
+
+            let mut middle_min = rest_min;
+            middle_min[axis] = cut_min[axis];
+            let mut middle_max = rest_max;
+            middle_max[axis] = cut_max[axis];
+            rest = Cuboid { min: Point3::from_coords(middle_min), max: Point3::from_coords(middle_max) };
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use super::{Cuboid, Point3, Rotation};
+
+    fn cuboid(min: [isize; 3], max: [isize; 3]) -> Cuboid {
+        Cuboid::new(Point3::from_coords(min), Point3::from_coords(max)).unwrap()
+    }
+
+    #[test]
+    fn all_rotations_are_24_distinct_proper_rotations() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+        assert_eq!(rotations.iter().collect::<HashSet<&Rotation>>().len(), 24);
+        assert!(rotations.iter().all(|rotation| rotation.determinant() == 1));
+    }
+
+    #[test]
+    fn rotations_are_closed_under_then() {
+        let rotations = Rotation::all();
+        let set: HashSet<Rotation> = rotations.iter().copied().collect();
+        for first in &rotations {
+            for second in &rotations {
+                assert!(set.contains(&first.then(second)), "{:?} then {:?}", first, second);
+            }
+        }
+    }
+
+    #[test]
+    fn then_applies_in_order() {
+        let point = Point3::new(1, 2, 3);
+        let rotations = Rotation::all();
+        for first in &rotations {
+            for second in &rotations {
+                assert_eq!(first.then(second).apply(&point), second.apply(&first.apply(&point)));
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_every_rotation() {
+        let point = Point3::new(-4, 5, 7);
+        for rotation in Rotation::all() {
+            assert_eq!(rotation.then(&rotation.inverse()), Rotation::IDENTITY, "{:?}", rotation);
+            assert_eq!(rotation.inverse().then(&rotation), Rotation::IDENTITY, "{:?}", rotation);
+            assert_eq!(rotation.inverse().apply(&rotation.apply(&point)), point);
+        }
+    }
+
+    #[test]
+    fn subtract_keeps_volume_without_overlaps() {
+        let whole = cuboid([0, 0, 0], [9, 9, 9]);
+        let cuts = [
+            cuboid([3, 3, 3], [5, 6, 7]),       // inside
+            cuboid([-5, 2, 2], [4, 20, 3]),     // through one side
+            cuboid([8, 8, 8], [12, 12, 12]),    // a corner
+            cuboid([0, 0, 0], [9, 9, 0]),       // one face
+            cuboid([-1, -1, -1], [10, 10, 10]), // all of it
+            cuboid([20, 20, 20], [21, 21, 21]), // none of it
+        ];
+        for cut in cuts {
+            let pieces = whole.subtract(&cut);
+            let cut_volume = whole.intersection(&cut).map_or(0, |overlap| overlap.volume());
+            assert_eq!(pieces.iter().map(Cuboid::volume).sum::<isize>() + cut_volume, whole.volume(), "{:?}", cut);
+            assert!(pieces.len() <= 6);
+            for (i, piece) in pieces.iter().enumerate() {
+                assert!(piece.within(&whole));
+                assert_eq!(piece.intersection(&cut), None, "{:?} overlaps the cut {:?}", piece, cut);
+                assert!(pieces[i + 1..].iter().all(|other| piece.intersection(other).is_none()));
+            }
+        }
+    }
+
+    #[test]
+    fn new_rejects_inside_out_boxes() {
+        assert_eq!(Cuboid::new(Point3::new(1, 0, 0), Point3::new(0, 5, 5)), None);
+        assert_eq!(cuboid([1, 2, 3], [1, 2, 3]).volume(), 1);
+        assert_eq!(cuboid([-1, -1, -1], [1, 1, 1]).volume(), 27);
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use super::day::ParseError;
+
+// (row, col), counted from the top left
+pub type Pos = (usize, usize);
+
+pub const NEIGHBORS4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+pub const NEIGHBORS8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// a rectangular map stored row major. a grid with a background goes on forever,
+// every cell past the edges holding that value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+    background: Option<T>
+}
+
+impl<T> Grid<T> {
+    // every row has to be as long as the first
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().find(|row| row.len() != cols) {
+            panic!("a row of {} cells in a grid {} wide", row.len(), cols);
+        }
+        Grid {
+            rows: rows.len(),
+            cols,
+            cells: rows.into_iter().flatten().collect(),
+            background: None
+        }
+    }
+
+    pub fn from_fn<F: FnMut(Pos) -> T>(rows: usize, cols: usize, cell: F) -> Grid<T> {
+        Grid {
+            rows,
+            cols,
+            cells: (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col))).map(cell).collect(),
+            background: None
+        }
+    }
+
+    pub fn with_background(self, background: T) -> Grid<T> {
+        Grid {
+            background: Some(background),
+            ..self
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn background(&self) -> Option<&T> {
+        self.background.as_ref()
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.0 * self.cols + pos.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.cols + pos.1])
+        } else {
+            None
+        }
+    }
+
+    // any cell of an infinite grid, off the edges of a finite one is None
+    pub fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        if row < 0 || col < 0 {
+            return self.background.as_ref();
+        }
+        self.get((row as usize, col as usize))
+            .or(self.background.as_ref())
+    }
+
+    // the step from pos, if it stays on the grid
+    pub fn offset(&self, (row, col): Pos, (row_d, col_d): (isize, isize)) -> Option<Pos> {
+        let (row, col) = (row as isize + row_d, col as isize + col_d);
+        if row < 0 || col < 0 {
+            return None;
+        }
+        Some((row as usize, col as usize)).filter(|pos| self.contains(*pos))
+    }
+
+    // the step from pos on a torus, leaving one edge comes back in at the opposite one
+    pub fn wrapping_offset(&self, (row, col): Pos, (row_d, col_d): (isize, isize)) -> Pos {
+        ((row as isize + row_d).rem_euclid(self.rows as isize) as usize,
+         (col as isize + col_d).rem_euclid(self.cols as isize) as usize)
+    }
+
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS4.iter().filter_map(move |delta| self.offset(pos, *delta))
+    }
+
+    // diagonals included
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS8.iter().filter_map(move |delta| self.offset(pos, *delta))
+    }
+
+    // row by row, left to right
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    // the background is mapped like any cell, so past the edges still means the same
+    pub fn map<U, F: FnMut(&T) -> U>(&self, mut cell: F) -> Grid<U> {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.cells.iter().map(&mut cell).collect(),
+            background: self.background.as_ref().map(cell)
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(value: T, rows: usize, cols: usize) -> Grid<T> {
+        Grid {
+            rows,
+            cols,
+            cells: vec![value; rows * cols],
+            background: None
+        }
+    }
+}
+
+impl Grid<usize> {
+    // rectangular map of single digits, one row per non-empty line
+    pub fn parse_digits(day: usize, content: &str) -> Result<Grid<usize>, ParseError> {
+        Grid::parse_cells(day, content, content, "digit", |c| c.to_digit(10).map(|digit| digit as usize))
+    }
+}
+
+impl Grid<char> {
+    // rectangular map of the given chars, one row per non-empty line of section
+    pub fn parse_chars(day: usize, content: &str, section: &str, allowed: &str, what: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_cells(day, content, section, what, |c| Some(c).filter(|c| allowed.contains(*c)))
+    }
+}
+
+impl<T> Grid<T> {
+    // section is content or a slice of it, so errors point into content.
+    // what names a cell for the errors, "not a <what>" when cell has no value for a char
+    pub fn parse_cells<F>(day: usize, content: &str, section: &str, what: &str, cell: F) -> Result<Grid<T>, ParseError>
+        where F: Fn(char) -> Option<T>
+    {
+        let lines: Vec<&str> = section.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let width = match lines.first() {
+            None => return Err(ParseError::eof(day, content, "no rows")),
+            Some(first) => first.chars().count()
+        };
+        let rows = lines.iter()
+            .map(|line| {
+                if line.chars().count() != width {
+                    return Err(ParseError::at(day, content, line, format!("expected {} cells per row", width)));
+                }
+                line.char_indices()
+                    .map(|(i, c)| cell(c)
+                        .ok_or_else(|| ParseError::at(day, content, &line[i..], format!("not a {}: {}", what, c))))
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<T>>, ParseError>>()?;
+        Ok(Grid::from_rows(rows))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is off a {}x{} grid", pos, self.rows, self.cols)
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is off a {}x{} grid", pos, rows, cols)
+        }
+    }
+}
+
+// one line per row, the cells back to back
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for cell in &self.cells[row * self.cols..(row + 1) * self.cols] {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Pos};
+
+    // 3x4, each cell its row * 10 + col
+    fn numbered() -> Grid<usize> {
+        Grid::from_fn(3, 4, |(row, col)| row * 10 + col)
+    }
+
+    fn sorted(positions: impl Iterator<Item = Pos>) -> Vec<Pos> {
+        let mut positions: Vec<Pos> = positions.collect();
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn neighbors4_stay_on_the_grid() {
+        let grid = numbered();
+        assert_eq!(sorted(grid.neighbors4((1, 1))), vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+        assert_eq!(sorted(grid.neighbors4((0, 2))), vec![(0, 1), (0, 3), (1, 2)]);
+        assert_eq!(sorted(grid.neighbors4((0, 0))), vec![(0, 1), (1, 0)]);
+        assert_eq!(sorted(grid.neighbors4((2, 3))), vec![(1, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn neighbors8_include_diagonals_and_stay_on_the_grid() {
+        let grid = numbered();
+        assert_eq!(sorted(grid.neighbors8((1, 1))),
+                   vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(sorted(grid.neighbors8((1, 3))), vec![(0, 2), (0, 3), (1, 2), (2, 2), (2, 3)]);
+        assert_eq!(sorted(grid.neighbors8((0, 0))), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(sorted(grid.neighbors8((2, 3))), vec![(1, 2), (1, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn offset_is_none_off_the_edges() {
+        let grid = numbered();
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (0, -1)), None);
+        assert_eq!(grid.offset((2, 3), (1, 0)), None);
+        assert_eq!(grid.offset((2, 3), (0, 1)), None);
+        assert_eq!(grid.offset((2, 3), (-2, -3)), Some((0, 0)));
+    }
+
+    #[test]
+    fn wrapping_offset_comes_back_in_at_the_opposite_edge() {
+        let grid = numbered();
+        assert_eq!(grid.wrapping_offset((0, 0), (-1, 0)), (2, 0));
+        assert_eq!(grid.wrapping_offset((0, 0), (0, -1)), (0, 3));
+        assert_eq!(grid.wrapping_offset((2, 3), (1, 1)), (0, 0));
+        assert_eq!(grid.wrapping_offset((1, 2), (0, 1)), (1, 3));
+        assert_eq!(grid.wrapping_offset((1, 2), (-7, 9)), (0, 3));
+    }
+
+    #[test]
+    fn background_fills_everything_past_the_edges() {
+        let finite = numbered();
+        assert_eq!(finite.background(), None);
+        assert_eq!(finite.get_signed(-1, 0), None);
+        assert_eq!(finite.get_signed(3, 0), None);
+        assert_eq!(finite.get_signed(2, 3), Some(&23));
+
+        let infinite = numbered().with_background(99);
+        assert_eq!(infinite.background(), Some(&99));
+        assert_eq!(infinite.get_signed(-1, -1), Some(&99));
+        assert_eq!(infinite.get_signed(0, 4), Some(&99));
+        assert_eq!(infinite.get_signed(100, 1), Some(&99));
+        assert_eq!(infinite.get_signed(1, 2), Some(&12));
+        assert_eq!(infinite.get((3, 0)), None); // only get_signed sees the background
+    }
+
+    #[test]
+    fn map_keeps_the_background() {
+        let doubled = numbered().with_background(99).map(|cell| cell * 2);
+        assert_eq!(doubled.get_signed(1, 2), Some(&24));
+        assert_eq!(doubled.get_signed(-1, 0), Some(&198));
+        assert_eq!(numbered().map(|cell| cell * 2).background(), None);
+    }
+
+    #[test]
+    fn positions_and_iter_go_row_by_row() {
+        let grid = numbered();
+        assert_eq!(grid.positions().take(5).collect::<Vec<Pos>>(), vec![(0, 0), (0, 1), (0, 2), (0, 3), (1, 0)]);
+        assert!(grid.iter().all(|((row, col), cell)| *cell == row * 10 + col));
+        assert_eq!(grid.len(), 12);
+    }
+
+    #[test]
+    fn parse_digits_points_at_the_bad_cell() {
+        let grid = Grid::parse_digits(9, "219\n398\n").unwrap();
+        assert_eq!((grid.rows(), grid.cols(), grid[(1, 2)]), (2, 3, 8));
+        assert_eq!(grid.to_string(), "219\n398");
+
+        let error = Grid::parse_digits(9, "219\n3x8\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (2, 2, "not a digit: x"));
+        let error = Grid::parse_digits(9, "219\n39\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (2, 1, "expected 3 cells per row"));
+        let error = Grid::parse_digits(9, "\n\n").unwrap_err();
+        assert_eq!(error.message, "no rows");
+    }
+}
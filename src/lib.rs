@@ -0,0 +1,63 @@
+pub mod bench;
+pub mod cache;
+pub mod cases;
+pub mod day;
+pub mod days;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod logging;
+pub mod output;
+pub mod parse;
+pub mod registry;
+pub mod remote;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod submit;
+pub mod verify;
+pub mod watch;
+
+use std::fmt;
+pub use day::{Answer, CancelToken, Day, DayMaker, ParseError};
+pub use registry::{Registered, Registry, DEFAULT_YEAR};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Answer,
+    pub part2: Answer
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownDay(usize, usize),
+    Parse(ParseError)
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::UnknownDay(year, day_num) => write!(f, "{} day {} is not registered", year, day_num),
+            SolveError::Parse(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(error: ParseError) -> Self {
+        SolveError::Parse(error)
+    }
+}
+
+// parse the input and answer both parts, without any timing or output
+pub fn solve(year: usize, day_num: usize, input: &str) -> Result<Answers, SolveError> {
+    let registered = Registry::new().get(year, day_num)
+        .ok_or(SolveError::UnknownDay(year, day_num))?;
+    let day = (registered.maker)(input)?;
+    Ok(Answers {
+        part1: day.part1(),
+        part2: day.part2()
+    })
+}
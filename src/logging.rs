@@ -0,0 +1,41 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+// info is plain progress for whoever runs the cli, debug and trace also say which module spoke
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = record.level().as_str().to_lowercase();
+        match record.level() {
+            Level::Info => eprintln!("{}", record.args()),
+            Level::Warn | Level::Error => eprintln!("{}: {}", level, record.args()),
+            _ => eprintln!("{} {}: {}", level, record.target(), record.args())
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+// 0 shows info and up, each -v adds a level below it and each -q takes one away
+pub fn init(verbosity: isize) {
+    let level = match verbosity {
+        v if v <= -3 => LevelFilter::Off,
+        -2 => LevelFilter::Error,
+        -1 => LevelFilter::Warn,
+        0 => LevelFilter::Info,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace
+    };
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
@@ -1,98 +1,364 @@
-#![feature(process_exitcode_placeholder)]
-#![feature(linked_list_cursors)]
-#![feature(map_first_last)]
-#![feature(int_log)]
 extern crate chrono;
 extern crate chrono_tz;
 
-mod day;
-mod days;
-
-use clap::{App, Arg};
-use std::fs;
+use clap::{App, Arg, SubCommand};
 use std::process::ExitCode;
-use std::time::Instant;
-use day::DayMaker;
+use std::time::Duration;
+use aoc2021::{bench, cache, cases, generate, logging, output, remote, runner, scaffold, submit, verify, watch, Registered, Registry, DEFAULT_YEAR};
+use aoc2021::bench::BenchConfig;
+use aoc2021::cache::Cache;
+use aoc2021::output::Format;
+use aoc2021::runner::Input;
 use chrono::{Utc, TimeZone, Datelike};
 use chrono_tz::US::Eastern;
+use log::{error, info};
 
-const ARG_DAY: &'static str = "day";
-const ARG_TEST: &'static str = "test";
-const DAY_MAKERS: &'static [DayMaker] = &[
-    days::day1::Day1::from_content,
-    days::day2::Day2::from_content,
-    days::day3::Day3::from_content,
-    days::day4::Day4::from_content,
-    days::day5::Day5::from_content,
-    days::day6::Day6::from_content,
-    days::day7::Day7::from_content,
-    days::day8::Day8::from_content,
-    days::day9::Day9::from_content,
-    days::day10::Day10::from_content,
-    days::day11::Day11::from_content,
-    days::day12::Day12::from_content,
-    days::day13::Day13::from_content,
-    days::day14::Day14::from_content,
-    days::day15::Day15::from_content,
-    days::day16::Day16::from_content,
-    days::day17::Day17::from_content,
-    days::day18::Day18::from_content,
-    days::day19::Day19::from_content,
-    days::day20::Day20::from_content,
-    days::day21::Day21::from_content,
-    days::day22::Day22::from_content,
-    days::day23::Day23::from_content,
-    days::day24::Day24::from_content,
-    days::day25::Day25::from_content
-];
+const ARG_DAY: &str = "day";
+const ARG_TEST: &str = "test";
+const ARG_VERIFY: &str = "verify";
+const ARG_PART: &str = "part";
+const ARG_BENCH: &str = "bench";
+const ARG_WARMUP: &str = "warmup";
+const ARG_BUDGET: &str = "budget";
+const ARG_BENCH_JSON: &str = "bench-json";
+const ARG_FORMAT: &str = "format";
+const ARG_INPUT: &str = "input";
+const ARG_INPUT_DIR: &str = "input-dir";
+const INPUT_STDIN: &str = "-";
+const ARG_YEAR: &str = "year";
+const ARG_JOBS: &str = "jobs";
+const ARG_TIMEOUT: &str = "timeout";
+const ARG_NO_CACHE: &str = "no-cache";
+const ARG_WATCH: &str = "watch";
+const ARG_VERBOSE: &str = "verbose";
+const ARG_QUIET: &str = "quiet";
+const ARG_BASE_URL: &str = "base-url";
+const ARG_SESSION_FILE: &str = "session-file";
+const ARG_SIZE: &str = "size";
+const ARG_SEED: &str = "seed";
+const CMD_NEW: &str = "new";
+const CMD_FETCH: &str = "fetch";
+const CMD_SUBMIT: &str = "submit";
+const CMD_GEN: &str = "gen";
+const DAY_ALL: &str = "all";
+const DAY_LIST: &str = "list";
 
 fn main() -> ExitCode {
+    let default_year = DEFAULT_YEAR.to_string();
     let app = App::new("AoC-2021-01")
         .version("0.0.1")
         .about("Advent of Code 2021")
         .arg(Arg::with_name(ARG_DAY)
-            .help("which day to run (default: the current day of December ET)")
+            .help("which day to run, \"all\" for every registered day of the year, or \"list\" to show what is registered (default: the current day of December ET)")
             .index(1))
         .arg(Arg::with_name(ARG_TEST)
-            .help("test mode (the sample data)")
+            .help("test mode (the sample data), also checks the dayNN/tests cases, json and csv report them as records of their own")
             .short("t"))
+        .arg(Arg::with_name(ARG_VERIFY)
+            .help("compare answers against dayNN/answers.txt (both inputs unless -t) and fail on any mismatch")
+            .long(ARG_VERIFY))
+        .arg(Arg::with_name(ARG_PART)
+            .help("only run one part")
+            .short("p")
+            .long(ARG_PART)
+            .takes_value(true)
+            .possible_values(&["1", "2"]))
+        .arg(Arg::with_name(ARG_BENCH)
+            .help("benchmark mode, re-create the day from its input this many times and report timing statistics")
+            .long(ARG_BENCH)
+            .takes_value(true)
+            .value_name("iterations"))
+        .arg(Arg::with_name(ARG_WARMUP)
+            .help("unmeasured iterations to run before benchmarking (default: 1)")
+            .long(ARG_WARMUP)
+            .takes_value(true)
+            .requires(ARG_BENCH))
+        .arg(Arg::with_name(ARG_BUDGET)
+            .help("stop benchmarking a day after this many seconds of measured iterations")
+            .long(ARG_BUDGET)
+            .takes_value(true)
+            .value_name("seconds")
+            .requires(ARG_BENCH))
+        .arg(Arg::with_name(ARG_BENCH_JSON)
+            .help("also write benchmark results as JSON to this file")
+            .long(ARG_BENCH_JSON)
+            .takes_value(true)
+            .value_name("path")
+            .requires(ARG_BENCH))
+        .arg(Arg::with_name(ARG_FORMAT)
+            .help("how to print results, json and csv carry one record per day for scripts")
+            .long(ARG_FORMAT)
+            .takes_value(true)
+            .possible_values(&output::FORMATS)
+            .default_value("text"))
+        .arg(Arg::with_name(ARG_INPUT)
+            .help("read the day's input from this file instead, or \"-\" for stdin")
+            .long(ARG_INPUT)
+            .takes_value(true)
+            .value_name("path")
+            .conflicts_with_all(&[ARG_TEST, ARG_VERIFY, ARG_INPUT_DIR]))
+        .arg(Arg::with_name(ARG_INPUT_DIR)
+            .help("directory holding the dayNN directories of inputs and answers")
+            .long(ARG_INPUT_DIR)
+            .takes_value(true)
+            .value_name("dir"))
+        .arg(Arg::with_name(ARG_YEAR)
+            .help("which event year the day belongs to")
+            .long(ARG_YEAR)
+            .takes_value(true)
+            .default_value(&default_year))
+        .arg(Arg::with_name(ARG_JOBS)
+            .help("run the days concurrently on this many threads")
+            .short("j")
+            .long(ARG_JOBS)
+            .takes_value(true)
+            .value_name("N")
+            .default_value("1"))
+        .arg(Arg::with_name(ARG_TIMEOUT)
+            .help("give up on a day after this many seconds and report it as timed out")
+            .long(ARG_TIMEOUT)
+            .takes_value(true)
+            .value_name("seconds"))
+        .arg(Arg::with_name(ARG_NO_CACHE)
+            .help("run every part even when .cache already has its answer for this input and solver version")
+            .long(ARG_NO_CACHE))
+        .arg(Arg::with_name(ARG_WATCH)
+            .help("clear the screen and run again whenever the day's dayNN/*.txt files change (source changes still need a rebuild)")
+            .long(ARG_WATCH))
+        .arg(Arg::with_name(ARG_VERBOSE)
+            .help("log more, repeat for solver debugging and tracing")
+            .short("v")
+            .long(ARG_VERBOSE)
+            .multiple(true))
+        .arg(Arg::with_name(ARG_QUIET)
+            .help("log less, repeat to drop warnings and then errors")
+            .short("q")
+            .long(ARG_QUIET)
+            .multiple(true))
+        .arg(Arg::with_name(ARG_BASE_URL)
+            .help("Advent of Code compatible server to fetch from (default: $AOC_BASE_URL, then https://adventofcode.com)")
+            .long(ARG_BASE_URL)
+            .takes_value(true)
+            .value_name("url"))
+        .arg(Arg::with_name(ARG_SESSION_FILE)
+            .help("file holding the session token, used when $AOC_SESSION is not set")
+            .long(ARG_SESSION_FILE)
+            .takes_value(true)
+            .value_name("path")
+            .default_value(remote::DEFAULT_SESSION_FILE))
+        .subcommand(SubCommand::with_name(CMD_NEW)
+            .about("start a new day: its input directory, a src/days module and its registration")
+            .arg(Arg::with_name(ARG_DAY)
+                .help("which day to add")
+                .required(true)
+                .index(1)))
+        .subcommand(SubCommand::with_name(CMD_FETCH)
+            .about("download a day's input into dayNN/input.txt unless it is already there")
+            .arg(Arg::with_name(ARG_DAY)
+                .help("which day to fetch")
+                .required(true)
+                .index(1)))
+        .subcommand(SubCommand::with_name(CMD_SUBMIT)
+            .about("solve a part against dayNN/input.txt and submit the answer, unless dayNN/submissions.txt already knows it")
+            .arg(Arg::with_name(ARG_DAY)
+                .help("which day to submit")
+                .required(true)
+                .index(1))
+            .arg(Arg::with_name(ARG_PART)
+                .help("which part to submit")
+                .required(true)
+                .index(2)
+                .possible_values(&["1", "2"])))
+        .subcommand(SubCommand::with_name(CMD_GEN)
+            .about("print a random input for a day, eg to pipe into --input - for stress testing")
+            .arg(Arg::with_name(ARG_DAY)
+                .help("which day to generate for")
+                .required(true)
+                .index(1))
+            .arg(Arg::with_name(ARG_SIZE)
+                .help("how big an input, what it counts depends on the day (lines, grid side, scanners...)")
+                .long(ARG_SIZE)
+                .takes_value(true)
+                .value_name("N")
+                .default_value("10"))
+            .arg(Arg::with_name(ARG_SEED)
+                .help("the same seed gives the same input (default: a random one, logged so it can be repeated)")
+                .long(ARG_SEED)
+                .takes_value(true)
+                .value_name("S")))
         .get_matches();
 
-    let day_num: usize = match app.value_of(ARG_DAY) {
-        Some(day) => day.parse().expect("day is not a num"),
-        None => Eastern.from_utc_datetime(&Utc::now().naive_utc())
-            .day() as usize
+    logging::init(app.occurrences_of(ARG_VERBOSE) as isize - app.occurrences_of(ARG_QUIET) as isize);
+
+    let test: bool = app.occurrences_of(ARG_TEST) > 0;
+    let verify: bool = app.occurrences_of(ARG_VERIFY) > 0;
+    let parts: Vec<usize> = match app.value_of(ARG_PART) {
+        Some(part) => vec![part.parse().expect("part is not a num")],
+        None => runner::PARTS.to_vec()
+    };
+    let year: usize = app.value_of(ARG_YEAR).unwrap().parse().expect("year is not a num");
+    let root = app.value_of(ARG_INPUT_DIR)
+        .map(String::from)
+        .unwrap_or_else(|| runner::default_input_dir(year));
+    let root = root.as_str();
+    let input: Input = match app.value_of(ARG_INPUT) {
+        Some(INPUT_STDIN) => Input::Stdin,
+        Some(path) => Input::File(String::from(path)),
+        None => Input::Tree { root: String::from(root), test }
+    };
+    let jobs: usize = app.value_of(ARG_JOBS).unwrap().parse().expect("jobs is not a num");
+    let timeout: Option<Duration> = app.value_of(ARG_TIMEOUT)
+        .map(|secs| Duration::from_secs_f64(secs.parse().expect("timeout is not a num")));
+    let cache: Option<Cache> = if app.is_present(ARG_NO_CACHE) {
+        None
+    } else {
+        Some(Cache::new(cache::DEFAULT_CACHE_DIR))
     };
-    let day_idx = day_num - 1;
+    let format: Format = app.value_of(ARG_FORMAT).unwrap().parse().unwrap();
+    let bench: Option<BenchConfig> = app.value_of(ARG_BENCH).map(|iterations| BenchConfig {
+        iterations: iterations.parse().expect("iterations is not a num"),
+        warmup: app.value_of(ARG_WARMUP).unwrap_or("1").parse().expect("warmup is not a num"),
+        budget: app.value_of(ARG_BUDGET)
+            .map(|secs| Duration::from_secs_f64(secs.parse().expect("budget is not a num"))),
+        parts: parts.clone()
+    });
 
-    if DAY_MAKERS.len() <= day_idx {
-        eprintln!("Day {} is not registered yet.", day_num);
-        return ExitCode::FAILURE;
+    if let Some(new_app) = app.subcommand_matches(CMD_NEW) {
+        let day_num: usize = new_app.value_of(ARG_DAY).unwrap().parse().expect("day is not a num");
+        return match scaffold::new_day(root, year, day_num) {
+            Ok(created) => {
+                created.iter().for_each(|path| info!("Wrote {}", path));
+                ExitCode::SUCCESS
+            }
+            Err(desc) => {
+                error!("Unable to add day {}: {}", day_num, desc);
+                ExitCode::FAILURE
+            }
+        };
     }
 
-    let test: bool = app.occurrences_of(ARG_TEST) > 0;
-    let content_path = format!("./day{:02}/{}.txt", day_num, if test {"test"} else {"input"});
-    println!("Loading input from {}", content_path);
-    let content = match fs::read_to_string(&content_path) {
-        Ok(content) => content,
-        Err(error) => {
-            eprintln!("Unable to open input file {}: {}", content_path, error);
-            return ExitCode::FAILURE;
+    if let Some(fetch_app) = app.subcommand_matches(CMD_FETCH) {
+        let day_num: usize = fetch_app.value_of(ARG_DAY).unwrap().parse().expect("day is not a num");
+        let fetched = remote::Server::from_env(app.value_of(ARG_BASE_URL), app.value_of(ARG_SESSION_FILE).unwrap())
+            .and_then(|server| remote::fetch_input(&server, root, year, day_num));
+        return match fetched {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(desc) => {
+                error!("Unable to fetch day {}: {}", day_num, desc);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(gen_app) = app.subcommand_matches(CMD_GEN) {
+        let day_num: usize = gen_app.value_of(ARG_DAY).unwrap().parse().expect("day is not a num");
+        let size: usize = gen_app.value_of(ARG_SIZE).unwrap().parse().expect("size is not a num");
+        let seed: u64 = match gen_app.value_of(ARG_SEED) {
+            Some(seed) => seed.parse().expect("seed is not a num"),
+            None => {
+                let seed = rand::random();
+                info!("Generating day {} with seed {}", day_num, seed);
+                seed
+            }
+        };
+        return match generate::generate(day_num, size, seed) {
+            Ok(content) => {
+                print!("{}", content);
+                ExitCode::SUCCESS
+            }
+            Err(desc) => {
+                error!("Unable to generate day {}: {}", day_num, desc);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(submit_app) = app.subcommand_matches(CMD_SUBMIT) {
+        let day_num: usize = submit_app.value_of(ARG_DAY).unwrap().parse().expect("day is not a num");
+        let part: usize = submit_app.value_of(ARG_PART).unwrap().parse().expect("part is not a num");
+        let submitted = Registry::new().get(year, day_num)
+            .ok_or_else(|| format!("day {} of {} is not registered", day_num, year))
+            .and_then(|registered| runner::guarded_load_and_run(registered.maker, day_num,
+                                                                &Input::Tree { root: String::from(root), test: false },
+                                                                &[part], timeout))
+            .and_then(|run| {
+                let answer = &run.parts[0].answer;
+                info!("{}", runner::format_part(part, answer));
+                let server = remote::Server::from_env(app.value_of(ARG_BASE_URL), app.value_of(ARG_SESSION_FILE).unwrap())?;
+                let mut log = submit::SubmissionLog::load(root, day_num)?;
+                submit::submit(&server, &mut log, year, day_num, part, answer)
+            });
+        return match submitted {
+            Ok(outcome) => {
+                println!("day {} part {}: {}", day_num, part, outcome);
+                exit_code(outcome == submit::Outcome::Correct)
+            }
+            Err(desc) => {
+                error!("Unable to submit day {} part {}: {}", day_num, part, desc);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let registry = Registry::new();
+    let days: Vec<Registered> = match app.value_of(ARG_DAY) {
+        Some(DAY_LIST) => {
+            for registered in registry.iter() {
+                println!("{} day {}", registered.year, registered.day);
+            }
+            return ExitCode::SUCCESS;
+        }
+        Some(DAY_ALL) => {
+            if app.is_present(ARG_INPUT) {
+                error!("An input path only applies to a single day.");
+                return ExitCode::FAILURE;
+            }
+            let days = registry.year(year);
+            if days.is_empty() {
+                error!("No days of {} are registered yet.", year);
+                return ExitCode::FAILURE;
+            }
+            days
+        }
+        day => {
+            let day_num: usize = match day {
+                Some(day) => day.parse().expect("day is not a num"),
+                None => Eastern.from_utc_datetime(&Utc::now().naive_utc())
+                    .day() as usize
+            };
+            match registry.get(year, day_num) {
+                Some(registered) => vec![registered],
+                None => {
+                    error!("Day {} of {} is not registered yet.", day_num, year);
+                    return ExitCode::FAILURE;
+                }
+            }
         }
     };
 
-    let start = Instant::now();
-    match DAY_MAKERS[day_idx](&content) {
-        Ok(mut day) => {
-            println!("part 1: {}", day.part1());
-            println!("part 2: {}", day.part2());
-        },
-        Err(desc) => {
-            eprintln!("Error creating day: {}", desc);
-            return ExitCode::FAILURE;
+    let run_selected = || if let Some(config) = &bench {
+        bench::bench_days(&days, &input, config, app.value_of(ARG_BENCH_JSON))
+    } else if verify {
+        verify::verify_days(&days, root, test, &parts, timeout)
+    } else if test && format == Format::Text {
+        let ran = runner::run_days(&days, &input, &parts, timeout, cache.as_ref(), format, jobs);
+        cases::check_days(&days, root, &parts, timeout) && ran
+    } else {
+        runner::run_days(&days, &input, &parts, timeout, cache.as_ref(), format, jobs)
+    };
+    if app.is_present(ARG_WATCH) {
+        if let Err(desc) = watch::watch(&days, &input, run_selected) {
+            error!("Unable to watch: {}", desc);
         }
+        return ExitCode::FAILURE;
     }
-    println!("runtime: {:?}", start.elapsed());
-
-    return ExitCode::SUCCESS;
+    exit_code(run_selected())
 }
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::str::FromStr;
+use std::time::Duration;
+use log::{error, info};
+use serde_json::{json, Value};
+use super::day::Answer;
+use super::runner::{self, DayRecord, PartRun};
+
+pub const FORMATS: [&str; 3] = ["text", "json", "csv"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(word: &str) -> Result<Self, Self::Err> {
+        match word {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unsupported format {}", word))
+        }
+    }
+}
+
+// prints records as they arrive, json is held until finish so it comes out as one array
+pub struct Reporter {
+    format: Format,
+    table: bool,
+    json_records: Vec<Value>
+}
+
+impl Reporter {
+    pub fn new(format: Format, table: bool) -> Reporter {
+        match format {
+            Format::Text if table => println!("{:>3}  {:>20}  {:>20}  {:>12}  {:>12}  {:>12}  {:>12}",
+                                              "day", "part 1", "part 2", "parse", "prepare", "part 1 time", "part 2 time"),
+            Format::Csv => println!("year,day,input,part1,part2,parse_ns,prepare_ns,part1_ns,part2_ns,part1_cached,part2_cached,error"),
+            _ => {}
+        }
+        Reporter {
+            format,
+            table,
+            json_records: Vec::new()
+        }
+    }
+
+    pub fn report(&mut self, record: &DayRecord) {
+        match self.format {
+            Format::Text if self.table => print_table_row(record),
+            Format::Text => print_block(record),
+            Format::Json => self.json_records.push(record_json(record)),
+            Format::Csv => print_csv_row(record)
+        }
+    }
+
+    // total_time is wall clock, cpu_time what every thread spent on the days, more than the wall clock when they ran in parallel.
+    // cpu_time is missing where the platform has no way to ask for it
+    pub fn finish(self, total_time: Duration, cpu_time: Option<Duration>) {
+        let cpu = cpu_time.map_or_else(|| String::from("unavailable"), |time| format!("{:?}", time));
+        match self.format {
+            Format::Text if self.table => {
+                println!("total runtime: {:?}", total_time);
+                println!("cpu time: {}", cpu);
+            }
+            Format::Text => {}
+            Format::Json => {
+                println!("{}", serde_json::to_string_pretty(&Value::Array(self.json_records)).unwrap());
+                info!("total runtime: {:?}", total_time);
+                info!("cpu time: {}", cpu);
+            }
+            Format::Csv => {
+                info!("total runtime: {:?}", total_time);
+                info!("cpu time: {}", cpu);
+            }
+        }
+    }
+}
+
+fn print_table_row(record: &DayRecord) {
+    match &record.result {
+        Ok(run) => {
+            let answer = |part| run.part(part)
+                .map(|part_run| part_run.answer.inline())
+                .unwrap_or_else(|| String::from("-"));
+            let time = |part| run.part(part)
+                .map(format_time)
+                .unwrap_or_else(|| String::from("-"));
+            println!("{:>3}  {:>20}  {:>20}  {:>12}  {:>12}  {:>12}  {:>12}",
+                     record.day_num, answer(1), answer(2),
+                     format!("{:?}", run.parse_time),
+                     format!("{:?}", run.prepare_time),
+                     time(1),
+                     time(2));
+        }
+        Err(desc) => {
+            println!("{:>3}  FAILED: {}", record.day_num, desc);
+        }
+    }
+}
+
+fn print_block(record: &DayRecord) {
+    match &record.result {
+        Ok(run) => {
+            for part_run in run.parts.iter() {
+                println!("{}", runner::format_part(part_run.part, &part_run.answer));
+            }
+            println!("parse time: {:?}", run.parse_time);
+            println!("prepare time: {:?}", run.prepare_time);
+            for part_run in run.parts.iter() {
+                println!("part {} time: {}", part_run.part, format_time(part_run));
+            }
+            println!("runtime: {:?}", run.total_time());
+        }
+        Err(desc) => {
+            error!("Day {} failed: {}", record.day_num, desc);
+        }
+    }
+}
+
+// a cached part took no time worth reporting, it says where its answer came from instead
+fn format_time(part_run: &PartRun) -> String {
+    if part_run.cached {
+        String::from("cached")
+    } else {
+        format!("{:?}", part_run.time)
+    }
+}
+
+fn print_csv_row(record: &DayRecord) {
+    let mut fields: Vec<String> = vec![record.year.to_string(), record.day_num.to_string(), record.input.clone()];
+    match &record.result {
+        Ok(run) => {
+            for part in runner::PARTS {
+                fields.push(run.part(part)
+                    .map(|part_run| part_run.answer.to_string())
+                    .unwrap_or_default());
+            }
+            fields.push(run.parse_time.as_nanos().to_string());
+            fields.push(run.prepare_time.as_nanos().to_string());
+            for part in runner::PARTS {
+                fields.push(run.part(part)
+                    .map(|part_run| part_run.time.as_nanos().to_string())
+                    .unwrap_or_default());
+            }
+            for part in runner::PARTS {
+                fields.push(run.part(part)
+                    .map(|part_run| part_run.cached.to_string())
+                    .unwrap_or_default());
+            }
+            fields.push(String::new());
+        }
+        Err(desc) => {
+            fields.extend(vec![String::new(); 8]);
+            fields.push(desc.clone());
+        }
+    }
+    println!("{}", fields.iter().map(|field| csv_escape(field)).collect::<Vec<String>>().join(","));
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+fn record_json(record: &DayRecord) -> Value {
+    match &record.result {
+        Ok(run) => json!({
+            "year": record.year,
+            "day": record.day_num,
+            "input": record.input,
+            "parse_ns": run.parse_time.as_nanos() as u64,
+            "prepare_ns": run.prepare_time.as_nanos() as u64,
+            "parts": run.parts.iter().map(|part_run| json!({
+                "part": part_run.part,
+                "answer": answer_json(&part_run.answer),
+                "time_ns": part_run.time.as_nanos() as u64,
+                "cached": part_run.cached
+            })).collect::<Vec<Value>>(),
+            "error": null
+        }),
+        Err(desc) => json!({
+            "year": record.year,
+            "day": record.day_num,
+            "input": record.input,
+            "parse_ns": null,
+            "prepare_ns": null,
+            "parts": [],
+            "error": desc
+        })
+    }
+}
+
+pub fn answer_json(answer: &Answer) -> Value {
+    match answer {
+        Answer::Int(num) => json!(num),
+        Answer::BigInt(num) => match i64::try_from(*num) {
+            Ok(num) => json!(num),
+            Err(_) => json!(num.to_string()) // past what json numbers hold exactly
+        },
+        Answer::Str(word) => json!(word),
+        Answer::Grid(rows) => json!(rows),
+        Answer::NotApplicable => Value::Null
+    }
+}
@@ -0,0 +1,222 @@
+use std::str::FromStr;
+use super::day::ParseError;
+use super::grid::Grid;
+
+// the pieces every day's from_content is built from. each takes a fragment sliced out of the content,
+// never a copy, so a failure can say which line and column it was at
+#[derive(Clone, Copy)]
+pub struct Parser<'a> {
+    day: usize,
+    content: &'a str
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(day: usize, content: &'a str) -> Parser<'a> {
+        Parser { day, content }
+    }
+
+    pub fn content(&self) -> &'a str {
+        self.content
+    }
+
+    pub fn error<S: Into<String>>(&self, fragment: &str, message: S) -> ParseError {
+        ParseError::at(self.day, self.content, fragment, message)
+    }
+
+    // for what is missing rather than wrong
+    pub fn eof<S: Into<String>>(&self, message: S) -> ParseError {
+        ParseError::eof(self.day, self.content, message)
+    }
+
+    // trimmed, blank lines skipped
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> {
+        self.content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+    }
+
+    // runs of lines between blank lines, each trimmed
+    pub fn blocks(&self) -> Vec<&'a str> {
+        let mut blocks = Vec::new();
+        let mut start: Option<usize> = None;
+        let mut end = 0;
+        let mut offset = 0;
+        for line in self.content.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                if let Some(block_start) = start.take() {
+                    blocks.push(self.content[block_start..end].trim());
+                }
+            } else {
+                start.get_or_insert(offset);
+                end = offset + line.len();
+            }
+            offset += line.len();
+        }
+        if let Some(block_start) = start {
+            blocks.push(self.content[block_start..end].trim());
+        }
+        blocks
+    }
+
+    pub fn num<T: FromStr>(&self, word: &'a str) -> Result<T, ParseError> {
+        let word = word.trim();
+        word.parse().map_err(|_| self.error(word, format!("not a num: {}", word)))
+    }
+
+    // items between separators, each trimmed before item sees it
+    pub fn list<T, F>(&self, text: &'a str, separator: char, mut item: F) -> Result<Vec<T>, ParseError>
+        where F: FnMut(&'a str) -> Result<T, ParseError>
+    {
+        text.split(separator)
+            .map(|word| item(word.trim()))
+            .collect()
+    }
+
+    // eg "3,4,3,1,2"
+    pub fn nums<T: FromStr>(&self, text: &'a str, separator: char) -> Result<Vec<T>, ParseError> {
+        self.list(text, separator, |word| self.num(word))
+    }
+
+    // the two sides of the first separator, expected describes the whole when there is none
+    pub fn pair(&self, text: &'a str, separator: &str, expected: &str) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(separator)
+            .map(|(left, right)| (left.trim(), right.trim()))
+            .ok_or_else(|| self.error(text, format!("expected \"{}\"", expected)))
+    }
+
+    pub fn prefixed(&self, text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        text.trim().strip_prefix(prefix)
+            .map(str::trim)
+            .ok_or_else(|| self.error(text, format!("expected \"{}\"", prefix)))
+    }
+
+    // the value of "key=value"
+    pub fn key_value(&self, text: &'a str, key: &str) -> Result<&'a str, ParseError> {
+        text.trim().strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix('='))
+            .ok_or_else(|| self.error(text, format!("expected \"{}=<value>\"", key)))
+    }
+
+    // "a..b" with both ends included, a must not be past b
+    pub fn range<T: FromStr + PartialOrd>(&self, text: &'a str) -> Result<(T, T), ParseError> {
+        let (start, end) = self.pair(text, "..", "<start>..<end>")?;
+        let (start, end): (T, T) = (self.num(start)?, self.num(end)?);
+        if start > end {
+            return Err(self.error(text, "range start is past its end"));
+        }
+        Ok((start, end))
+    }
+
+    pub fn digit_grid(&self) -> Result<Grid<usize>, ParseError> {
+        Grid::parse_digits(self.day, self.content)
+    }
+
+    // section is the content or a block of it, what names a cell for errors
+    pub fn char_grid(&self, section: &'a str, allowed: &str, what: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_chars(self.day, self.content, section, allowed, what)
+    }
+
+    // exactly the count of items there should be
+    pub fn expect_len<T>(&self, items: Vec<T>, len: usize, fragment: &str, what: &str) -> Result<Vec<T>, ParseError> {
+        if items.len() == len {
+            Ok(items)
+        } else {
+            Err(self.error(fragment, format!("expected {} {}", len, what)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::day::ParseError;
+    use super::Parser;
+
+    fn at(error: ParseError) -> (usize, usize, String) {
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn lines_are_trimmed_and_blank_ones_skipped() {
+        let parser = Parser::new(1, "  199\n\n200  \n   \n208");
+        assert_eq!(parser.lines().collect::<Vec<&str>>(), vec!["199", "200", "208"]);
+    }
+
+    #[test]
+    fn blocks_split_on_blank_lines() {
+        let content = "\n\n--- a\n1,2\n  \n\n--- b\n3,4\n5,6\n\n";
+        let parser = Parser::new(19, content);
+        let blocks = parser.blocks();
+        assert_eq!(blocks, vec!["--- a\n1,2", "--- b\n3,4\n5,6"]);
+        // still slices of content, so errors inside a block point into content
+        assert_eq!(at(parser.error(&blocks[1][6..], "here")), (8, 1, String::from("here")));
+        assert!(Parser::new(19, "\n  \n").blocks().is_empty());
+    }
+
+    #[test]
+    fn num_and_nums_point_at_the_bad_word() {
+        let parser = Parser::new(6, "3,4,3\n1, x2,2");
+        let lines: Vec<&str> = parser.lines().collect();
+        assert_eq!(parser.nums::<usize>(lines[0], ','), Ok(vec![3, 4, 3]));
+        assert_eq!(at(parser.nums::<usize>(lines[1], ',').unwrap_err()), (2, 4, String::from("not a num: x2")));
+        assert_eq!(parser.num::<isize>(" -7 "), Ok(-7));
+    }
+
+    #[test]
+    fn pair_splits_on_the_first_separator() {
+        let parser = Parser::new(12, "start-A\nb - end-x\nnope");
+        let lines: Vec<&str> = parser.lines().collect();
+        assert_eq!(parser.pair(lines[0], "-", "<cave>-<cave>"), Ok(("start", "A")));
+        assert_eq!(parser.pair(lines[1], "-", "<cave>-<cave>"), Ok(("b", "end-x")));
+        assert_eq!(at(parser.pair(lines[2], "-", "<cave>-<cave>").unwrap_err()),
+                   (3, 1, String::from("expected \"<cave>-<cave>\"")));
+    }
+
+    #[test]
+    fn prefixed_and_key_value() {
+        let parser = Parser::new(17, "target area: x=20..30, y=-10..-5");
+        let rest = parser.prefixed(parser.content(), "target area:").unwrap();
+        assert_eq!(rest, "x=20..30, y=-10..-5");
+        let (x, y) = parser.pair(rest, ",", "x=<range>, y=<range>").unwrap();
+        assert_eq!(parser.key_value(x, "x"), Ok("20..30"));
+        assert_eq!(parser.key_value(y, "y"), Ok("-10..-5"));
+        assert_eq!(at(parser.key_value(y, "z").unwrap_err()), (1, 24, String::from("expected \"z=<value>\"")));
+        assert_eq!(at(parser.prefixed(rest, "area").unwrap_err()), (1, 14, String::from("expected \"area\"")));
+    }
+
+    #[test]
+    fn range_includes_both_ends_in_order() {
+        let parser = Parser::new(22, "on x=-5..5\noff x=5..-5\nflip x=1..z\non x=4");
+        let lines: Vec<&str> = parser.lines().collect();
+        fn word(line: &str) -> &str {
+            line.split_once('=').unwrap().1
+        }
+        assert_eq!(parser.range::<isize>(word(lines[0])), Ok((-5, 5)));
+        assert_eq!(at(parser.range::<isize>(word(lines[1])).unwrap_err()),
+                   (2, 7, String::from("range start is past its end")));
+        assert_eq!(at(parser.range::<isize>(word(lines[2])).unwrap_err()), (3, 11, String::from("not a num: z")));
+        assert_eq!(at(parser.range::<isize>(word(lines[3])).unwrap_err()),
+                   (4, 6, String::from("expected \"<start>..<end>\"")));
+    }
+
+    #[test]
+    fn expect_len_names_what_was_short() {
+        let parser = Parser::new(19, "1,2,3\n4,5");
+        let lines: Vec<&str> = parser.lines().collect();
+        assert_eq!(parser.expect_len(parser.nums::<isize>(lines[0], ',').unwrap(), 3, lines[0], "coordinates"), Ok(vec![1, 2, 3]));
+        assert_eq!(at(parser.expect_len(parser.nums::<isize>(lines[1], ',').unwrap(), 3, lines[1], "coordinates").unwrap_err()),
+                   (2, 1, String::from("expected 3 coordinates")));
+    }
+
+    #[test]
+    fn positions_count_chars_from_one() {
+        let content = "ab\n\u{e9}\u{e9}x\n";
+        let parser = Parser::new(3, content);
+        assert_eq!(at(parser.error(&content[0..], "start")), (1, 1, String::from("start")));
+        assert_eq!(at(parser.error(&content[7..], "after two accented chars")).0, 2);
+        assert_eq!(at(parser.error(&content[7..], "after two accented chars")).1, 3);
+        assert_eq!(at(parser.eof("missing")), (3, 1, String::from("missing")));
+        assert_eq!(at(Parser::new(3, "").eof("empty")), (1, 1, String::from("empty")));
+        // not a slice of content at all, pinned to the end
+        assert_eq!(at(parser.error("elsewhere", "lost")), (3, 1, String::from("lost")));
+    }
+}
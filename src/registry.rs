@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+use super::day::DayMaker;
+
+pub const DEFAULT_YEAR: usize = 2021;
+
+#[derive(Clone, Copy)]
+pub struct Registered {
+    pub year: usize,
+    pub day: usize,
+    pub maker: DayMaker,
+    pub version: u32,
+}
+
+// every solved day keyed by (year, day), filled in by each year's register_days! block
+#[derive(Default)]
+pub struct Registry {
+    days: BTreeMap<(usize, usize), Registered>
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        let mut registry = Registry::default();
+        super::days::register(&mut registry);
+        registry
+    }
+
+    pub fn insert(&mut self, year: usize, day: usize, maker: DayMaker, version: u32) {
+        if self.days.insert((year, day), Registered { year, day, maker, version }).is_some() {
+            panic!("{} day {} is registered twice", year, day);
+        }
+    }
+
+    pub fn get(&self, year: usize, day: usize) -> Option<Registered> {
+        self.days.get(&(year, day)).copied()
+    }
+
+    pub fn year(&self, year: usize) -> Vec<Registered> {
+        self.iter()
+            .filter(|registered| registered.year == year)
+            .collect()
+    }
+
+    pub fn years(&self) -> Vec<usize> {
+        let mut years: Vec<usize> = self.days.keys().map(|(year, _)| *year).collect();
+        years.dedup(); // keys are sorted
+        years
+    }
+
+    // in (year, day) order whatever order they were registered in
+    pub fn iter(&self) -> impl Iterator<Item = Registered> + '_ {
+        self.days.values().copied()
+    }
+}
+
+// declares each day's module and a register() for the year, eg
+//   register_days! { 2021; 1 => day1::Day1, 2 => day2::Day2 @ 2 }
+// a solver is version 1 until it says otherwise, bump it whenever a change could alter its answers
+// so results cached from the old solver are not served for the new one
+#[macro_export]
+macro_rules! register_days {
+    ($year:expr; $($day:expr => $module:ident :: $solver:ident $(@ $version:literal)?),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub fn register(registry: &mut $crate::registry::Registry) {
+            $(registry.insert($year, $day, $module::$solver::from_content, *[1, $($version)?].last().unwrap());)*
+        }
+    };
+}
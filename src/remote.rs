@@ -0,0 +1,239 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use log::info;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const DEFAULT_SESSION_FILE: &str = ".session";
+const USER_AGENT: &str = "aoc2021 runner (github.com/tlmorgen/aoc2021)";
+
+// an Advent of Code compatible server, the real one unless pointed elsewhere for testing
+pub struct Server {
+    base_url: String,
+    session: Option<String>
+}
+
+impl Server {
+    // the base url comes from the flag, then AOC_BASE_URL, and the session from AOC_SESSION, then the file.
+    // a missing session only matters once a request is made
+    pub fn from_env(base_url: Option<&str>, session_file: &str) -> Result<Server, String> {
+        let base_url = base_url.map(String::from)
+            .or_else(|| env::var(BASE_URL_ENV).ok())
+            .unwrap_or_else(|| String::from(DEFAULT_BASE_URL));
+        let session = match env::var(SESSION_ENV) {
+            Ok(session) => Some(session),
+            Err(_) if Path::new(session_file).exists() => Some(fs::read_to_string(session_file)
+                .map_err(|error| format!("unable to read session file {}: {}", session_file, error))?),
+            Err(_) => None
+        };
+        Ok(Server::new(&base_url, session.as_deref()))
+    }
+
+    pub fn new(base_url: &str, session: Option<&str>) -> Server {
+        Server {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            session: session
+                .map(|session| String::from(session.trim()))
+                .filter(|session| !session.is_empty())
+        }
+    }
+
+    pub fn day_url(&self, year: usize, day_num: usize) -> String {
+        format!("{}/{}/day/{}", self.base_url, year, day_num)
+    }
+
+    fn session_cookie(&self) -> Result<String, String> {
+        self.session.as_ref()
+            .map(|session| format!("session={}", session))
+            .ok_or_else(|| format!("no session token, set {} or write it to the session file", SESSION_ENV))
+    }
+
+    pub fn get(&self, url: &str) -> Result<String, String> {
+        let response = ureq::get(url)
+            .set("Cookie", &self.session_cookie()?)
+            .set("User-Agent", USER_AGENT)
+            .call();
+        read_response(url, response)
+    }
+
+    pub fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let response = ureq::post(url)
+            .set("Cookie", &self.session_cookie()?)
+            .set("User-Agent", USER_AGENT)
+            .send_form(form);
+        read_response(url, response)
+    }
+}
+
+fn read_response(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, String> {
+    match response {
+        Ok(response) => response.into_string()
+            .map_err(|error| format!("unable to read response from {}: {}", url, error)),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            let summary: String = body.split_whitespace().collect::<Vec<&str>>().join(" ");
+            // the server turns a stale or mistyped session away with one of these
+            let hint = if matches!(status, 400 | 401 | 403) {", is the session token right?"} else {""};
+            Err(format!("{} answered {}{}: {}", url, status, hint, summary.chars().take(200).collect::<String>()))
+        }
+        Err(error) => Err(format!("unable to reach {}: {}", url, error))
+    }
+}
+
+pub fn input_path(root: &str, day_num: usize) -> String {
+    format!("{}/day{:02}/input.txt", root, day_num)
+}
+
+// an input that is already there is never downloaded again, an empty one (as `new` leaves it) is not there yet
+pub fn fetch_input(server: &Server, root: &str, year: usize, day_num: usize) -> Result<String, String> {
+    let path = input_path(root, day_num);
+    if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+        info!("{} is already fetched", path);
+        return Ok(path);
+    }
+    let content = server.get(&format!("{}/input", server.day_url(year, day_num)))?;
+
+    let dir = format!("{}/day{:02}", root, day_num);
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("unable to create {}: {}", dir, error))?;
+    let partial_path = format!("{}.part", path); // so an interrupted download never looks cached
+    fs::write(&partial_path, content)
+        .and_then(|_| fs::rename(&partial_path, &path))
+        .map_err(|error| format!("unable to write {}: {}", path, error))?;
+    info!("Fetched {}", path);
+    Ok(path)
+}
+
+// a plain HTTP server on a throwaway port for tests to point a Server at
+#[cfg(test)]
+pub mod stub {
+    use std::env;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    // answers every request with the same status and body, and keeps each request it was sent
+    pub struct StubServer {
+        pub base_url: String,
+        requests: Arc<Mutex<Vec<String>>>
+    }
+
+    impl StubServer {
+        pub fn start(status: u16, body: &'static str) -> StubServer {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let seen = Arc::clone(&requests);
+            thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let request = read_request(&stream);
+                    seen.lock().unwrap().push(request);
+                    let _ = write!(stream, "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                   status, body.len(), body);
+                }
+            });
+            StubServer { base_url, requests }
+        }
+
+        pub fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    // the head and body as sent, enough to check the path, cookie and form
+    fn read_request(stream: &TcpStream) -> String {
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_len = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_len = value.trim().parse().unwrap_or(0);
+                }
+            }
+            request.push_str(&line);
+        }
+        let mut body = vec![0; content_len];
+        let _ = reader.read_exact(&mut body);
+        request + "\r\n" + &String::from_utf8_lossy(&body)
+    }
+
+    // an empty directory of its own under the system temp dir
+    pub fn scratch_dir(name: &str) -> String {
+        let dir = env::temp_dir().join(format!("aoc2021-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use super::stub::{scratch_dir, StubServer};
+    use super::{fetch_input, input_path, Server, SESSION_ENV};
+
+    #[test]
+    fn fetch_saves_the_input_and_never_downloads_it_again() {
+        let stub = StubServer::start(200, "199\n200\n208\n");
+        let server = Server::new(&stub.base_url, Some("abc123\n"));
+        let root = scratch_dir("fetch-once");
+
+        let path = fetch_input(&server, &root, 2021, 1).unwrap();
+        assert_eq!(path, input_path(&root, 1));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "199\n200\n208\n");
+        assert!(!Path::new(&format!("{}.part", path)).exists());
+
+        assert_eq!(fetch_input(&server, &root, 2021, 1).unwrap(), path);
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2021/day/1/input "), "{}", requests[0]);
+        assert!(requests[0].contains("session=abc123\r\n"), "{}", requests[0]);
+    }
+
+    #[test]
+    fn fetch_replaces_an_empty_placeholder() {
+        let stub = StubServer::start(200, "3,4,3,1,2\n");
+        let server = Server::new(&stub.base_url, Some("abc123"));
+        let root = scratch_dir("fetch-placeholder");
+        fs::create_dir_all(format!("{}/day06", root)).unwrap();
+        fs::write(input_path(&root, 6), "").unwrap();
+
+        let path = fetch_input(&server, &root, 2021, 6).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "3,4,3,1,2\n");
+        assert_eq!(stub.requests().len(), 1);
+    }
+
+    #[test]
+    fn fetch_without_a_session_says_so_and_sends_nothing() {
+        let stub = StubServer::start(200, "unused");
+        let server = Server::new(&stub.base_url, Some("  \n"));
+        let root = scratch_dir("fetch-no-session");
+
+        let error = fetch_input(&server, &root, 2021, 2).unwrap_err();
+        assert!(error.contains("no session token") && error.contains(SESSION_ENV), "{}", error);
+        assert!(stub.requests().is_empty());
+        assert!(!Path::new(&input_path(&root, 2)).exists());
+    }
+
+    #[test]
+    fn fetch_with_a_refused_session_says_so_and_writes_nothing() {
+        let stub = StubServer::start(400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n");
+        let server = Server::new(&stub.base_url, Some("expired"));
+        let root = scratch_dir("fetch-bad-session");
+
+        let error = fetch_input(&server, &root, 2021, 3).unwrap_err();
+        assert!(error.contains("answered 400, is the session token right?"), "{}", error);
+        assert!(error.contains("Puzzle inputs differ by user. Please log in"), "{}", error);
+        assert!(!Path::new(&input_path(&root, 3)).exists());
+    }
+}
@@ -0,0 +1,412 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{error, info, warn};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use super::cache::Cache;
+use super::cases;
+use super::day::{Answer, CancelToken, Day, DayMaker, ParseError};
+use super::output::{Format, Reporter};
+use super::registry::{Registered, DEFAULT_YEAR};
+
+pub const PARTS: [usize; 2] = [1, 2];
+
+pub struct DayRun {
+    pub parse_time: Duration,
+    // the day's set-up after parsing, apart so slow alignment and the like is not taken for parsing
+    pub prepare_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+pub struct PartRun {
+    pub part: usize,
+    pub answer: Answer,
+    pub time: Duration,
+    pub cached: bool,
+}
+
+impl DayRun {
+    pub fn part(&self, part: usize) -> Option<&PartRun> {
+        self.parts.iter().find(|part_run| part_run.part == part)
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.parts.iter().fold(self.parse_time + self.prepare_time, |total, part_run| total + part_run.time)
+    }
+}
+
+pub fn input_name(test: bool) -> &'static str {
+    if test {"test"} else {"input"}
+}
+
+// this year's inputs sit at the top of the tree, other years under a directory of their own
+pub fn default_input_dir(year: usize) -> String {
+    if year == DEFAULT_YEAR {
+        String::from(".")
+    } else {
+        format!("./{}", year)
+    }
+}
+
+pub fn content_path(root: &str, day_num: usize, test: bool) -> String {
+    format!("{}/day{:02}/{}.txt", root, day_num, input_name(test))
+}
+
+// where a day's content comes from, the tree layout unless a path or stdin was given
+#[derive(Debug, Clone)]
+pub enum Input {
+    Tree { root: String, test: bool },
+    File(String),
+    Stdin
+}
+
+impl Input {
+    // what records call the input, "test" or "input" for the tree
+    pub fn name(&self) -> String {
+        match self {
+            Input::Tree { test, .. } => String::from(input_name(*test)),
+            Input::File(path) => path.clone(),
+            Input::Stdin => String::from("stdin")
+        }
+    }
+
+    pub fn path(&self, day_num: usize) -> String {
+        match self {
+            Input::Tree { root, test } => content_path(root, day_num, *test),
+            Input::File(path) => path.clone(),
+            Input::Stdin => String::from("stdin")
+        }
+    }
+
+    pub fn read(&self, day_num: usize) -> Result<String, String> {
+        match self {
+            Input::Stdin => {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)
+                    .map_err(|error| format!("unable to read stdin: {}", error))?;
+                Ok(content)
+            }
+            _ => {
+                let path = self.path(day_num);
+                fs::read_to_string(&path)
+                    .map_err(|error| format!("unable to open input file {}: {}", path, error))
+            }
+        }
+    }
+}
+
+pub enum RunError {
+    Parse(ParseError),
+    TimedOut(Duration)
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(error) => write!(f, "unable to parse: {}", error),
+            RunError::TimedOut(timeout) => write!(f, "timed out after {:?}", timeout)
+        }
+    }
+}
+
+impl From<ParseError> for RunError {
+    fn from(error: ParseError) -> Self {
+        RunError::Parse(error)
+    }
+}
+
+pub fn load_and_run(maker: DayMaker, day_num: usize, input: &Input, parts: &[usize], timeout: Option<Duration>) -> Result<DayRun, String> {
+    let content = input.read(day_num)?;
+    run(maker, &content, parts, timeout)
+        .map_err(|error| describe_error(error, day_num, input))
+}
+
+fn describe_error(error: RunError, day_num: usize, input: &Input) -> String {
+    match error {
+        RunError::Parse(error) => format!("unable to parse {}: {}", input.path(day_num), error),
+        error => error.to_string()
+    }
+}
+
+// parts the cache already has answers for are not run, when every part is cached the day is not even parsed
+pub fn cached_load_and_run(registered: Registered, input: &Input, parts: &[usize], timeout: Option<Duration>, cache: &Cache) -> Result<DayRun, String> {
+    let content = input.read(registered.day)?;
+    let cached = cache.load(&registered, &content, parts);
+    let missing: Vec<usize> = parts.iter().zip(cached.iter())
+        .filter(|(_, answer)| answer.is_none())
+        .map(|(part, _)| *part)
+        .collect();
+
+    let mut fresh = DayRun {
+        parse_time: Duration::ZERO,
+        prepare_time: Duration::ZERO,
+        parts: Vec::new()
+    };
+    if !missing.is_empty() {
+        fresh = panic::catch_unwind(AssertUnwindSafe(|| run(registered.maker, &content, &missing, timeout)
+            .map_err(|error| describe_error(error, registered.day, input))))
+            .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(&payload))))?;
+        if let Err(desc) = cache.store(&registered, &content, &fresh.parts) {
+            warn!("Unable to cache day {}: {}", registered.day, desc);
+        }
+    }
+
+    let mut fresh_parts = fresh.parts.into_iter();
+    Ok(DayRun {
+        parse_time: fresh.parse_time,
+        prepare_time: fresh.prepare_time,
+        parts: parts.iter().zip(cached)
+            .map(|(part, answer)| match answer {
+                Some(answer) => PartRun {
+                    part: *part,
+                    answer,
+                    time: Duration::ZERO,
+                    cached: true
+                },
+                None => fresh_parts.next().unwrap()
+            })
+            .collect()
+    })
+}
+
+pub fn format_part(part: usize, answer: &Answer) -> String {
+    if answer.is_multiline() {
+        format!("part {}:\n{}", part, answer)
+    } else {
+        format!("part {}: {}", part, answer)
+    }
+}
+
+pub fn guarded_load_and_run(maker: DayMaker, day_num: usize, input: &Input, parts: &[usize], timeout: Option<Duration>) -> Result<DayRun, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| load_and_run(maker, day_num, input, parts, timeout)))
+        .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(&payload))))
+}
+
+pub fn guarded_run(maker: DayMaker, content: &str, parts: &[usize], timeout: Option<Duration>) -> Result<DayRun, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| run(maker, content, parts, timeout).map_err(|error| error.to_string())))
+        .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(&payload))))
+}
+
+// the timeout covers the whole day, parsing included. with one the day runs on a thread of its own that is left
+// behind when the time is up, days that check the token give up soon after and the rest finish unheard
+pub fn run(maker: DayMaker, content: &str, parts: &[usize], timeout: Option<Duration>) -> Result<DayRun, RunError> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return run_stages(maker, content, parts, None)
+    };
+    let (sender, receiver) = mpsc::channel();
+    let (content, parts) = (content.to_string(), parts.to_vec());
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_stages(maker, &content, &parts, Some(timeout))));
+        // nobody is waiting any more when it comes too late
+        let _ = sender.send(result);
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(result)) => result,
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(_) => Err(RunError::TimedOut(timeout))
+    }
+}
+
+fn run_stages(maker: DayMaker, content: &str, parts: &[usize], timeout: Option<Duration>) -> Result<DayRun, RunError> {
+    let cancel = timeout.map_or(CancelToken::never(), CancelToken::after);
+    let check_cancel = || match timeout {
+        Some(timeout) if cancel.is_cancelled() => Err(RunError::TimedOut(timeout)),
+        _ => Ok(())
+    };
+
+    let start = Instant::now();
+    let day = maker(content)?;
+    let parse_time = start.elapsed();
+    check_cancel()?;
+
+    let start = Instant::now();
+    day.prepare(&cancel);
+    let prepare_time = start.elapsed();
+    check_cancel()?;
+
+    let mut part_runs = Vec::new();
+    for part in parts {
+        part_runs.push(run_part(day.as_ref(), *part, &cancel));
+        check_cancel()?;
+    }
+    Ok(DayRun {
+        parse_time,
+        prepare_time,
+        parts: part_runs
+    })
+}
+
+pub fn run_part(day: &dyn Day, part: usize, cancel: &CancelToken) -> PartRun {
+    let start = Instant::now();
+    let answer = match part {
+        1 => day.part1_until(cancel),
+        2 => day.part2_until(cancel),
+        _ => panic!("no such part {}", part)
+    };
+    PartRun {
+        part,
+        answer,
+        time: start.elapsed(),
+        cached: false
+    }
+}
+
+// one day against one input, what every output format reports
+pub struct DayRecord {
+    pub year: usize,
+    pub day_num: usize,
+    pub input: String,
+    pub result: Result<DayRun, String>,
+}
+
+impl DayRecord {
+    pub fn load_and_run(registered: Registered, input: &Input, parts: &[usize], timeout: Option<Duration>, cache: Option<&Cache>) -> DayRecord {
+        DayRecord {
+            year: registered.year,
+            day_num: registered.day,
+            input: input.name(),
+            result: match cache {
+                Some(cache) => cached_load_and_run(registered, input, parts, timeout, cache),
+                None => guarded_load_and_run(registered.maker, registered.day, input, parts, timeout)
+            }
+        }
+    }
+}
+
+// jobs above one runs the days on a pool of that many threads, records still come out in day order.
+// with a cache, answers it has are reported without running the parts again. json and csv of the sample
+// inputs carry each day's dayNN/tests cases as records after it, text leaves them to cases::check_days
+pub fn run_days(days: &[Registered], input: &Input, parts: &[usize], timeout: Option<Duration>, cache: Option<&Cache>, format: Format, jobs: usize) -> bool {
+    let mut all_ok = true;
+    let start = Instant::now();
+    let start_cpu = cpu_time();
+    let mut reporter = Reporter::new(format, days.len() > 1);
+    let mut report = |record: DayRecord| {
+        all_ok &= record.result.is_ok();
+        reporter.report(&record);
+    };
+    let cases_root = match input {
+        Input::Tree { root, test: true } if format != Format::Text => Some(root.as_str()),
+        _ => None
+    };
+    let day_records = |registered: &Registered| {
+        let mut records = vec![DayRecord::load_and_run(*registered, input, parts, timeout, cache)];
+        if let Some(root) = cases_root {
+            records.extend(cases::case_records(*registered, root, parts, timeout));
+        }
+        records
+    };
+    if jobs > 1 {
+        let pool = match ThreadPoolBuilder::new().num_threads(jobs).build() {
+            Ok(pool) => pool,
+            Err(error) => {
+                error!("Unable to start {} jobs: {}", jobs, error);
+                return false;
+            }
+        };
+        let records: Vec<Vec<DayRecord>> = pool.install(|| days.par_iter()
+            .map(day_records)
+            .collect());
+        records.into_iter().flatten().for_each(report);
+    } else {
+        for registered in days {
+            if days.len() == 1 {
+                info!("Loading input from {}", input.path(registered.day));
+            }
+            day_records(registered).into_iter().for_each(&mut report);
+        }
+    }
+    let cpu = cpu_time().zip(start_cpu).map(|(end, start)| end.saturating_sub(start));
+    reporter.finish(start.elapsed(), cpu);
+    all_ok
+}
+
+// user and system time of the whole process, so threads a day starts itself (day 19) count as well as the days
+// running side by side on the pool. that makes it the sum of every day's cpu time, not one day's
+#[cfg(unix)]
+pub fn cpu_time() -> Option<Duration> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: getrusage only writes the struct it is given
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: it succeeded, so the struct is filled in
+    let usage = unsafe { usage.assume_init() };
+    let timeval = |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+    Some(timeval(usage.ru_utime) + timeval(usage.ru_stime))
+}
+
+#[cfg(not(unix))]
+pub fn cpu_time() -> Option<Duration> {
+    None
+}
+
+fn panic_message(payload: &Box<dyn std::any::Any + Send>) -> &str {
+    if let Some(desc) = payload.downcast_ref::<&str>() {
+        desc
+    } else if let Some(desc) = payload.downcast_ref::<String>() {
+        desc
+    } else {
+        "unknown panic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use super::super::day::{Answer, Day, ParseError};
+    use super::{cpu_time, guarded_run, run, RunError};
+
+    // never looks at the cancel token
+    struct Stubborn;
+
+    impl Day for Stubborn {
+        fn part1(&self) -> Answer {
+            thread::sleep(Duration::from_secs(5));
+            Answer::Int(1)
+        }
+
+        fn part2(&self) -> Answer {
+            panic!("no part 2")
+        }
+    }
+
+    fn stubborn(_content: &str) -> Result<Box<dyn Day>, ParseError> {
+        Ok(Box::new(Stubborn))
+    }
+
+    #[test]
+    fn timeout_stops_waiting_for_a_day_that_ignores_it() {
+        let start = Instant::now();
+        let result = run(stubborn, "", &[1], Some(Duration::from_millis(50)));
+        assert!(matches!(result, Err(RunError::TimedOut(_))));
+        assert!(start.elapsed() < Duration::from_secs(2), "waited {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn panics_on_the_timed_thread_are_still_reported() {
+        let result = guarded_run(stubborn, "", &[2], Some(Duration::from_secs(5)));
+        assert_eq!(result.err(), Some(String::from("panicked: no part 2")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cpu_time_counts_other_threads() {
+        let before = cpu_time().unwrap();
+        thread::spawn(|| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(200) {
+                std::hint::black_box(start.elapsed());
+            }
+        }).join().unwrap();
+        let spent = cpu_time().unwrap() - before;
+        assert!(spent >= Duration::from_millis(50), "only {:?} counted", spent);
+    }
+}
@@ -0,0 +1,163 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use super::registry::DEFAULT_YEAR;
+
+pub const DAYS_DIR: &str = "./src/days";
+
+// the starting point every day module is copied from, "{N}" becomes the day number
+const DAY_TEMPLATE: &str = "\
+use super::super::day::{Answer, Day, ParseError};
+use super::super::parse::Parser;
+
+const DAY: usize = {N};
+
+pub struct Day{N} {
+    lines: Vec<String>
+}
+
+impl Day{N} {
+    pub fn from_content(content: &str) -> Result<Box<dyn Day>, ParseError> {
+        let parser = Parser::new(DAY, content);
+        let lines: Vec<String> = parser.lines()
+            .map(String::from)
+            .collect();
+        if lines.is_empty() {
+            return Err(parser.eof(\"no input\"));
+        }
+        Ok(Box::new(Day{N} {
+            lines
+        }))
+    }
+}
+
+impl Day for Day{N} {
+    fn part1(&self) -> Answer {
+        self.lines.len().into()
+    }
+
+    fn part2(&self) -> Answer {
+        Answer::NotApplicable
+    }
+}
+";
+
+// lays out dayNN/ under the input root, src/days/dayN.rs and its registration, returning what it created
+pub fn new_day(root: &str, year: usize, day_num: usize) -> Result<Vec<String>, String> {
+    if year != DEFAULT_YEAR {
+        return Err(format!("only {} has a days module to add to", DEFAULT_YEAR));
+    }
+    if !(1..=25).contains(&day_num) {
+        return Err(format!("there is no day {} in an event", day_num));
+    }
+    let mod_path = format!("{}/mod.rs", DAYS_DIR);
+    let mod_content = fs::read_to_string(&mod_path)
+        .map_err(|error| format!("unable to open {} (run from the repository root): {}", mod_path, error))?;
+    let mod_content = register(&mod_content, day_num)?;
+    let module_path = format!("{}/day{}.rs", DAYS_DIR, day_num);
+    if Path::new(&module_path).exists() {
+        return Err(format!("{} already exists", module_path));
+    }
+
+    let mut created = Vec::new();
+    let input_dir = format!("{}/day{:02}", root, day_num);
+    fs::create_dir_all(&input_dir)
+        .map_err(|error| format!("unable to create {}: {}", input_dir, error))?;
+    for name in ["input", "test"] {
+        let path = format!("{}/{}.txt", input_dir, name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => created.push(path),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(error) => return Err(format!("unable to create {}: {}", path, error))
+        }
+    }
+
+    let mut module = OpenOptions::new().write(true).create_new(true).open(&module_path)
+        .map_err(|error| format!("unable to create {}: {}", module_path, error))?;
+    module.write_all(DAY_TEMPLATE.replace("{N}", &day_num.to_string()).as_bytes())
+        .map_err(|error| format!("unable to write {}: {}", module_path, error))?;
+    created.push(module_path);
+
+    fs::write(&mod_path, mod_content)
+        .map_err(|error| format!("unable to write {}: {}", mod_path, error))?;
+    created.push(mod_path);
+    Ok(created)
+}
+
+// rewrites the register_days! block with the new day in order, the last entry without a comma
+fn register(mod_content: &str, day_num: usize) -> Result<String, String> {
+    let mut head: Vec<&str> = Vec::new();
+    let mut entries: Vec<(usize, String)> = Vec::new();
+    let mut tail: Vec<&str> = Vec::new();
+    for line in mod_content.lines() {
+        let entry = line.split_once("=>")
+            .and_then(|(day, _)| day.trim().parse::<usize>().ok());
+        match entry {
+            Some(day) if tail.is_empty() => entries.push((day, String::from(line.trim().trim_end_matches(',')))),
+            _ if entries.is_empty() => head.push(line),
+            _ => tail.push(line)
+        }
+    }
+    if entries.is_empty() {
+        return Err(String::from("no register_days! entries to add to"));
+    }
+    if entries.iter().any(|(day, _)| *day == day_num) {
+        return Err(format!("day {} is already registered", day_num));
+    }
+    entries.push((day_num, format!("{} => day{}::Day{}", day_num, day_num, day_num)));
+    entries.sort_by_key(|(day, _)| *day);
+
+    let last_idx = entries.len() - 1;
+    let lines: Vec<String> = head.iter().map(|line| line.to_string())
+        .chain(entries.iter().enumerate()
+            .map(|(idx, (_, entry))| format!("    {}{}", entry, if idx == last_idx { "" } else { "," })))
+        .chain(tail.iter().map(|line| line.to_string()))
+        .collect();
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::register;
+
+    const MOD_CONTENT: &str = "\
+crate::register_days! {
+    2021;
+    1 => day1::Day1,
+    2 => day2::Day2 @ 2,
+    5 => day5::Day5
+}
+";
+
+    #[test]
+    fn new_day_goes_in_order() {
+        assert_eq!(register(MOD_CONTENT, 3).unwrap(), "\
+crate::register_days! {
+    2021;
+    1 => day1::Day1,
+    2 => day2::Day2 @ 2,
+    3 => day3::Day3,
+    5 => day5::Day5
+}
+");
+    }
+
+    #[test]
+    fn new_last_day_is_the_one_without_a_comma() {
+        assert_eq!(register(MOD_CONTENT, 12).unwrap(), "\
+crate::register_days! {
+    2021;
+    1 => day1::Day1,
+    2 => day2::Day2 @ 2,
+    5 => day5::Day5,
+    12 => day12::Day12
+}
+");
+    }
+
+    #[test]
+    fn registered_day_is_refused() {
+        assert_eq!(register(MOD_CONTENT, 2), Err(String::from("day 2 is already registered")));
+        assert_eq!(register("pub mod day1;\n", 2), Err(String::from("no register_days! entries to add to")));
+    }
+}
@@ -0,0 +1,224 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// the cheapest way found to a goal, states runs from the start to the goal both included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub cost: usize,
+    pub states: Vec<S>
+}
+
+// every state seen gets an index, so the frontier and the parent links only hold numbers
+struct Seen<S> {
+    states: Vec<S>,
+    idxs: HashMap<S, usize>,
+    parents: Vec<Option<usize>>
+}
+
+impl<S: Hash + Eq + Clone> Seen<S> {
+    fn new(start: S) -> Seen<S> {
+        Seen {
+            idxs: HashMap::from([(start.clone(), 0)]),
+            states: vec![start],
+            parents: vec![None]
+        }
+    }
+
+    // the index of state and whether it is new
+    fn insert(&mut self, state: S) -> (usize, bool) {
+        if let Some(idx) = self.idxs.get(&state) {
+            return (*idx, false);
+        }
+        let idx = self.states.len();
+        self.idxs.insert(state.clone(), idx);
+        self.states.push(state);
+        self.parents.push(None);
+        (idx, true)
+    }
+
+    fn path(&self, cost: usize, goal_idx: usize) -> Path<S> {
+        let mut idxs = vec![goal_idx];
+        while let Some(parent) = self.parents[*idxs.last().unwrap()] {
+            idxs.push(parent);
+        }
+        Path {
+            cost,
+            states: idxs.into_iter().rev().map(|idx| self.states[idx].clone()).collect()
+        }
+    }
+}
+
+// successors gives each state reachable in one step with what the step costs
+pub fn dijkstra<S, I, FN, FG>(start: S, successors: FN, is_goal: FG) -> Option<Path<S>>
+    where S: Hash + Eq + Clone,
+          I: IntoIterator<Item = (S, usize)>,
+          FN: FnMut(&S) -> I,
+          FG: FnMut(&S) -> bool
+{
+    astar(start, successors, |_| 0, is_goal)
+}
+
+// dijkstra led by heuristic, which must never guess more than the real cost left to a goal
+pub fn astar<S, I, FN, FH, FG>(start: S, mut successors: FN, mut heuristic: FH, mut is_goal: FG) -> Option<Path<S>>
+    where S: Hash + Eq + Clone,
+          I: IntoIterator<Item = (S, usize)>,
+          FN: FnMut(&S) -> I,
+          FH: FnMut(&S) -> usize,
+          FG: FnMut(&S) -> bool
+{
+    let mut frontier = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut seen = Seen::new(start);
+    let mut costs = vec![0];
+    while let Some(Reverse((_, cost, idx))) = frontier.pop() {
+        if cost > costs[idx] {
+            continue; // a cheaper way here was already expanded
+        }
+        if is_goal(&seen.states[idx]) {
+            return Some(seen.path(cost, idx));
+        }
+        for (next, step_cost) in successors(&seen.states[idx]) {
+            let next_cost = cost + step_cost;
+            let estimate = heuristic(&next);
+            let (next_idx, new) = seen.insert(next);
+            if new {
+                costs.push(next_cost);
+            } else if next_cost >= costs[next_idx] {
+                continue;
+            }
+            costs[next_idx] = next_cost;
+            seen.parents[next_idx] = Some(idx);
+            frontier.push(Reverse((next_cost + estimate, next_cost, next_idx)));
+        }
+    }
+    None
+}
+
+// fewest steps, each costing one
+pub fn bfs<S, I, FN, FG>(start: S, mut successors: FN, mut is_goal: FG) -> Option<Path<S>>
+    where S: Hash + Eq + Clone,
+          I: IntoIterator<Item = S>,
+          FN: FnMut(&S) -> I,
+          FG: FnMut(&S) -> bool
+{
+    let mut frontier = VecDeque::from([(0, 0)]);
+    let mut seen = Seen::new(start);
+    while let Some((steps, idx)) = frontier.pop_front() {
+        if is_goal(&seen.states[idx]) {
+            return Some(seen.path(steps, idx));
+        }
+        for next in successors(&seen.states[idx]) {
+            let (next_idx, new) = seen.insert(next);
+            if new {
+                seen.parents[next_idx] = Some(idx);
+                frontier.push_back((steps + 1, next_idx));
+            }
+        }
+    }
+    None
+}
+
+// how many ways there are from start to a goal, successors giving each next state with how many ways it is
+// reached in one step. the states must not loop back on themselves, a goal ends its path
+pub fn count_paths<S, I, FN, FG>(start: S, mut successors: FN, mut is_goal: FG) -> usize
+    where S: Hash + Eq + Clone,
+          I: IntoIterator<Item = (S, usize)>,
+          FN: FnMut(&S) -> I,
+          FG: FnMut(&S) -> bool
+{
+    fn count<S, I, FN, FG>(state: S, successors: &mut FN, is_goal: &mut FG, memo: &mut HashMap<S, usize>) -> usize
+        where S: Hash + Eq + Clone,
+              I: IntoIterator<Item = (S, usize)>,
+              FN: FnMut(&S) -> I,
+              FG: FnMut(&S) -> bool
+    {
+        if let Some(paths) = memo.get(&state) {
+            return *paths;
+        }
+        let paths = if is_goal(&state) {
+            1
+        } else {
+            successors(&state).into_iter()
+                .map(|(next, ways)| ways * count(next, successors, is_goal, memo))
+                .sum()
+        };
+        memo.insert(state, paths);
+        paths
+    }
+    count(start, &mut successors, &mut is_goal, &mut HashMap::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, count_paths, dijkstra, Path};
+
+    // a->b 1, a->c 4, b->c 2, b->d 6, c->d 3, and e that nothing reaches
+    fn edges(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('c', 2), ('d', 6)],
+            'c' => vec![('d', 3)],
+            'e' => vec![('a', 1)],
+            _ => vec![]
+        }
+    }
+
+    // 5x5 grid of step costs, moving onto a cell costs its digit
+    const COSTS: [[usize; 5]; 5] = [
+        [1, 1, 6, 3, 7],
+        [1, 3, 8, 1, 3],
+        [2, 1, 3, 6, 5],
+        [3, 6, 9, 4, 9],
+        [7, 4, 6, 3, 4],
+    ];
+
+    fn grid_steps(&(row, col): &(usize, usize)) -> Vec<((usize, usize), usize)> {
+        let mut steps = Vec::new();
+        if row > 0 { steps.push((row - 1, col)); }
+        if row < 4 { steps.push((row + 1, col)); }
+        if col > 0 { steps.push((row, col - 1)); }
+        if col < 4 { steps.push((row, col + 1)); }
+        steps.into_iter().map(|pos| (pos, COSTS[pos.0][pos.1])).collect()
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        assert_eq!(dijkstra('a', edges, |node| *node == 'd'), Some(Path { cost: 6, states: vec!['a', 'b', 'c', 'd'] }));
+        assert_eq!(dijkstra('a', edges, |node| *node == 'a'), Some(Path { cost: 0, states: vec!['a'] }));
+        assert_eq!(dijkstra('a', edges, |node| *node == 'e'), None);
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra() {
+        let goal = (4, 4);
+        let manhattan = |&(row, col): &(usize, usize)| (goal.0 - row) + (goal.1 - col); // every step costs at least 1
+        let plain = dijkstra((0, 0), grid_steps, |pos| *pos == goal).unwrap();
+        let guided = astar((0, 0), grid_steps, manhattan, |pos| *pos == goal).unwrap();
+        assert_eq!(guided.cost, plain.cost);
+        assert_eq!(plain.cost, 1 + 2 + 1 + 3 + 6 + 4 + 3 + 4);
+        for path in [&plain, &guided] {
+            assert_eq!((path.states[0], *path.states.last().unwrap()), ((0, 0), goal));
+            let walked: usize = path.states[1..].iter().map(|(row, col)| COSTS[*row][*col]).sum();
+            assert_eq!(walked, path.cost);
+        }
+    }
+
+    #[test]
+    fn bfs_counts_steps_not_costs() {
+        let path = bfs('a', |node| edges(node).into_iter().map(|(next, _)| next), |node| *node == 'd').unwrap();
+        assert_eq!(path.cost, 2);
+        assert_eq!(path.states, vec!['a', 'b', 'd']);
+        assert_eq!(bfs('a', |node| edges(node).into_iter().map(|(next, _)| next), |node| *node == 'e'), None);
+    }
+
+    #[test]
+    fn count_paths_on_a_dag() {
+        // a->b, a->c, b->c, b->d, c->d: abd, abcd, acd
+        let unweighted = |node: &char| edges(node).into_iter().map(|(next, _)| (next, 1)).collect::<Vec<_>>();
+        assert_eq!(count_paths('a', unweighted, |node| *node == 'd'), 3);
+        assert_eq!(count_paths('c', unweighted, |node| *node == 'd'), 1);
+        assert_eq!(count_paths('d', unweighted, |node| *node == 'a'), 0);
+        // the edge weights as how many parallel edges there are: 1*6 + 1*2*3 + 4*3
+        assert_eq!(count_paths('a', edges, |node| *node == 'd'), 24);
+    }
+}
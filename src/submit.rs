@@ -0,0 +1,265 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use super::day::Answer;
+use super::remote::Server;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited,
+    AlreadySolved
+}
+
+impl Outcome {
+    // what the server's answer page says, it only ever comes back as prose
+    pub fn from_response(body: &str) -> Option<Outcome> {
+        if body.contains("That's the right answer") {
+            Some(Outcome::Correct)
+        } else if body.contains("That's not the right answer") {
+            if body.contains("your answer is too high") {
+                Some(Outcome::TooHigh)
+            } else if body.contains("your answer is too low") {
+                Some(Outcome::TooLow)
+            } else {
+                Some(Outcome::Wrong)
+            }
+        } else if body.contains("You gave an answer too recently") {
+            Some(Outcome::RateLimited)
+        } else if body.contains("You don't seem to be solving the right level") {
+            Some(Outcome::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    // only these say anything about the answer itself, worth keeping
+    fn is_final(&self) -> bool {
+        !matches!(self, Outcome::RateLimited | Outcome::AlreadySolved)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Outcome::Correct => "correct",
+            Outcome::Wrong => "wrong",
+            Outcome::TooHigh => "too-high",
+            Outcome::TooLow => "too-low",
+            Outcome::RateLimited => "rate-limited",
+            Outcome::AlreadySolved => "already-solved"
+        })
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(word: &str) -> Result<Self, Self::Err> {
+        match word {
+            "correct" => Ok(Outcome::Correct),
+            "wrong" => Ok(Outcome::Wrong),
+            "too-high" => Ok(Outcome::TooHigh),
+            "too-low" => Ok(Outcome::TooLow),
+            "rate-limited" => Ok(Outcome::RateLimited),
+            "already-solved" => Ok(Outcome::AlreadySolved),
+            _ => Err(format!("no such outcome {}", word))
+        }
+    }
+}
+
+// dayNN/submissions.txt holds one "<part> <outcome>: <answer>" line per answer the server judged
+pub struct SubmissionLog {
+    path: String,
+    entries: Vec<(usize, Outcome, String)>
+}
+
+pub fn submissions_path(root: &str, day_num: usize) -> String {
+    format!("{}/day{:02}/submissions.txt", root, day_num)
+}
+
+impl SubmissionLog {
+    pub fn load(root: &str, day_num: usize) -> Result<SubmissionLog, String> {
+        let path = submissions_path(root, day_num);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(format!("unable to open submissions file {}: {}", path, error))
+        };
+        let mut entries = Vec::new();
+        for (line_idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = line.split_once(':')
+                .and_then(|(key, answer)| key.split_once(' ')
+                    .map(|(part, outcome)| (part.parse::<usize>(), outcome.parse::<Outcome>(), answer.trim())));
+            match entry {
+                Some((Ok(part), Ok(outcome), answer)) => entries.push((part, outcome, String::from(answer))),
+                _ => return Err(format!("{} line {}: expected \"<part> <outcome>: <answer>\"", path, line_idx + 1))
+            }
+        }
+        Ok(SubmissionLog { path, entries })
+    }
+
+    // why this answer must not go to the server, if it is already known
+    pub fn known(&self, part: usize, answer: &str) -> Option<String> {
+        let num = answer.parse::<i128>().ok();
+        for (logged_part, outcome, logged) in self.entries.iter().filter(|(logged_part, ..)| *logged_part == part) {
+            let logged_num = logged.parse::<i128>().ok();
+            let reason = match (outcome, num, logged_num) {
+                (Outcome::Correct, ..) if logged == answer => format!("part {} was already accepted with this answer", logged_part),
+                (Outcome::Correct, ..) => format!("part {} was already accepted with {}", logged_part, logged),
+                _ if logged == answer => format!("{} was already submitted as {}", answer, outcome),
+                (Outcome::TooHigh, Some(num), Some(logged_num)) if num >= logged_num => format!("{} was too high already", logged),
+                (Outcome::TooLow, Some(num), Some(logged_num)) if num <= logged_num => format!("{} was too low already", logged),
+                _ => continue
+            };
+            return Some(reason);
+        }
+        None
+    }
+
+    pub fn record(&mut self, part: usize, outcome: Outcome, answer: &str) -> Result<(), String> {
+        if !outcome.is_final() {
+            return Ok(());
+        }
+        self.entries.push((part, outcome, String::from(answer)));
+        let content: String = self.entries.iter()
+            .map(|(part, outcome, answer)| format!("{} {}: {}\n", part, outcome, answer))
+            .collect();
+        fs::write(&self.path, content)
+            .map_err(|error| format!("unable to write submissions file {}: {}", self.path, error))
+    }
+}
+
+// posts the answer unless the log already knows how it went, and records what the server said
+pub fn submit(server: &Server, log: &mut SubmissionLog, year: usize, day_num: usize, part: usize, answer: &Answer) -> Result<Outcome, String> {
+    let answer = match answer {
+        Answer::Grid(_) => return Err(String::from("a grid has to be read and typed in by hand")),
+        Answer::NotApplicable => return Err(format!("part {} has no answer to submit", part)),
+        answer => answer.to_string()
+    };
+    if let Some(reason) = log.known(part, &answer) {
+        return Err(format!("not submitting {}: {}", answer, reason));
+    }
+    let url = format!("{}/answer", server.day_url(year, day_num));
+    let body = server.post_form(&url, &[("level", &part.to_string()), ("answer", &answer)])?;
+    let outcome = Outcome::from_response(&body)
+        .ok_or_else(|| format!("unrecognised response from {}", url))?;
+    log.record(part, outcome, &answer)?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::super::day::Answer;
+    use super::super::remote::stub::{scratch_dir, StubServer};
+    use super::super::remote::Server;
+    use super::{submissions_path, submit, Outcome, SubmissionLog};
+
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  \
+        If you're stuck, make sure you're using the full input data.</p></article>";
+
+    // a log in a day directory of its own, so record has somewhere to write
+    fn empty_log(name: &str, day_num: usize) -> (String, SubmissionLog) {
+        let root = scratch_dir(name);
+        fs::create_dir_all(format!("{}/day{:02}", root, day_num)).unwrap();
+        let log = SubmissionLog::load(&root, day_num).unwrap();
+        (root, log)
+    }
+
+    #[test]
+    fn outcome_from_each_response() {
+        let cases = [
+            ("<p>That's the right answer!  You are one gold star closer to saving your vacation.</p>", Some(Outcome::Correct)),
+            ("<p>That's not the right answer.  If you're stuck, make sure you're using the full input data.</p>", Some(Outcome::Wrong)),
+            (TOO_HIGH, Some(Outcome::TooHigh)),
+            ("<p>That's not the right answer; your answer is too low.</p>", Some(Outcome::TooLow)),
+            ("<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.</p>", Some(Outcome::RateLimited)),
+            ("<p>You don't seem to be solving the right level.  Did you already complete it?</p>", Some(Outcome::AlreadySolved)),
+            ("<p>Please log in.</p>", None),
+        ];
+        for (body, outcome) in cases {
+            assert_eq!(Outcome::from_response(body), outcome, "{}", body);
+        }
+    }
+
+    #[test]
+    fn outcome_names_read_back() {
+        for outcome in [Outcome::Correct, Outcome::Wrong, Outcome::TooHigh, Outcome::TooLow, Outcome::RateLimited, Outcome::AlreadySolved] {
+            assert_eq!(outcome.to_string().parse::<Outcome>(), Ok(outcome));
+        }
+    }
+
+    #[test]
+    fn known_answers_are_not_sent_again() {
+        let (_, mut log) = empty_log("submit-known", 1);
+        log.record(1, Outcome::TooHigh, "500").unwrap();
+        log.record(1, Outcome::TooLow, "100").unwrap();
+        log.record(1, Outcome::Wrong, "abc").unwrap();
+
+        assert_eq!(log.known(1, "500"), Some(String::from("500 was already submitted as too-high")));
+        assert_eq!(log.known(1, "600"), Some(String::from("500 was too high already")));
+        assert_eq!(log.known(1, "50"), Some(String::from("100 was too low already")));
+        assert_eq!(log.known(1, "abc"), Some(String::from("abc was already submitted as wrong")));
+        assert_eq!(log.known(1, "300"), None);
+        assert_eq!(log.known(2, "500"), None);
+
+        log.record(1, Outcome::Correct, "300").unwrap();
+        assert_eq!(log.known(1, "300"), Some(String::from("part 1 was already accepted with this answer")));
+        assert_eq!(log.known(1, "301"), Some(String::from("part 1 was already accepted with 300")));
+    }
+
+    #[test]
+    fn record_keeps_only_final_outcomes_and_reloads() {
+        let (root, mut log) = empty_log("submit-record", 2);
+        log.record(1, Outcome::RateLimited, "7").unwrap();
+        log.record(2, Outcome::AlreadySolved, "8").unwrap();
+        assert!(fs::metadata(submissions_path(&root, 2)).is_err());
+
+        log.record(1, Outcome::TooLow, "7").unwrap();
+        log.record(2, Outcome::Correct, "900").unwrap();
+        assert_eq!(fs::read_to_string(submissions_path(&root, 2)).unwrap(), "1 too-low: 7\n2 correct: 900\n");
+
+        let reloaded = SubmissionLog::load(&root, 2).unwrap();
+        assert_eq!(reloaded.known(1, "7"), Some(String::from("7 was already submitted as too-low")));
+        assert_eq!(reloaded.known(2, "901"), Some(String::from("part 2 was already accepted with 900")));
+        assert_eq!(reloaded.known(1, "8"), None);
+    }
+
+    #[test]
+    fn load_rejects_a_malformed_log() {
+        let (root, _) = empty_log("submit-malformed", 3);
+        fs::write(submissions_path(&root, 3), "# comment\n1 correct: 5\n1 maybe: 6\n").unwrap();
+        let error = SubmissionLog::load(&root, 3).err().unwrap();
+        assert!(error.ends_with("line 3: expected \"<part> <outcome>: <answer>\""), "{}", error);
+    }
+
+    #[test]
+    fn submit_posts_records_and_then_refuses() {
+        let stub = StubServer::start(200, TOO_HIGH);
+        let server = Server::new(&stub.base_url, Some("abc123"));
+        let (root, mut log) = empty_log("submit-end-to-end", 4);
+
+        assert_eq!(submit(&server, &mut log, 2021, 4, 1, &Answer::Int(4512)), Ok(Outcome::TooHigh));
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("POST /2021/day/4/answer "), "{}", requests[0]);
+        assert!(requests[0].contains("session=abc123\r\n"), "{}", requests[0]);
+        assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=4512"), "{}", requests[0]);
+        assert_eq!(fs::read_to_string(submissions_path(&root, 4)).unwrap(), "1 too-high: 4512\n");
+
+        let error = submit(&server, &mut log, 2021, 4, 1, &Answer::Int(5000)).unwrap_err();
+        assert_eq!(error, "not submitting 5000: 4512 was too high already");
+        assert!(submit(&server, &mut log, 2021, 4, 2, &Answer::NotApplicable).is_err());
+        assert!(submit(&server, &mut log, 2021, 4, 2, &Answer::Grid(vec![String::from("#")])).is_err());
+        assert_eq!(stub.requests().len(), 1);
+    }
+}
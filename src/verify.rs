@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::time::Duration;
+use super::day::Answer;
+use super::cases;
+use super::registry::Registered;
+use super::runner::{self, Input, PartRun, PARTS};
+
+// dayNN/answers.txt holds one "<input> <part>: <answer>" entry per line, eg "test 1: 7".
+// an answer is a number, "quoted text", n/a, or "grid" followed by one "|"-prefixed line per row
+pub struct AnswerKey {
+    expected: HashMap<(String, usize), Answer>
+}
+
+pub fn answers_path(root: &str, day_num: usize) -> String {
+    format!("{}/day{:02}/answers.txt", root, day_num)
+}
+
+impl AnswerKey {
+    pub fn load(root: &str, day_num: usize) -> Result<AnswerKey, String> {
+        let path = answers_path(root, day_num);
+        match fs::read_to_string(&path) {
+            Ok(content) => AnswerKey::from_content(&content)
+                .map_err(|desc| format!("{}: {}", path, desc)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(AnswerKey {
+                expected: HashMap::new()
+            }),
+            Err(error) => Err(format!("unable to open answers file {}: {}", path, error))
+        }
+    }
+
+    pub fn from_content(content: &str) -> Result<AnswerKey, String> {
+        let mut expected = HashMap::new();
+        let mut grid: Option<((String, usize), Vec<String>)> = None;
+        for (line_idx, line) in content.lines().enumerate() {
+            if let Some(row) = line.strip_prefix('|') {
+                match grid.as_mut() {
+                    Some((_, rows)) => rows.push(String::from(row)),
+                    None => return Err(format!("line {}: grid row outside of a grid answer", line_idx + 1))
+                }
+                continue;
+            }
+            if let Some((key, rows)) = grid.take() {
+                expected.insert(key, Answer::Grid(rows));
+            }
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, answer) = line.split_once(':')
+                .ok_or_else(|| format!("line {}: expected \"<input> <part>: <answer>\"", line_idx + 1))?;
+            let (input, part) = key.trim().split_once(' ')
+                .ok_or_else(|| format!("line {}: expected \"<input> <part>\"", line_idx + 1))?;
+            let part: usize = part.trim().parse()
+                .map_err(|_| format!("line {}: part is not a num: {}", line_idx + 1, part))?;
+            if !PARTS.contains(&part) {
+                return Err(format!("line {}: no such part {}", line_idx + 1, part));
+            }
+            let key = (String::from(input), part);
+            match answer.trim() {
+                "grid" => grid = Some((key, Vec::new())),
+                answer => {
+                    expected.insert(key, parse_answer(answer));
+                }
+            }
+        }
+        if let Some((key, rows)) = grid.take() {
+            expected.insert(key, Answer::Grid(rows));
+        }
+        Ok(AnswerKey { expected })
+    }
+
+    pub fn expected(&self, input: &str, part: usize) -> Option<&Answer> {
+        self.expected.get(&(String::from(input), part))
+    }
+}
+
+pub fn verify_days(days: &[Registered], root: &str, test_only: bool, parts: &[usize], timeout: Option<Duration>) -> bool {
+    let inputs: &[bool] = if test_only { &[true] } else { &[true, false] };
+    let mut checks = 0;
+    let mut failures = 0;
+    for registered in days {
+        let day_num = registered.day;
+        let answers = match AnswerKey::load(root, day_num) {
+            Ok(answers) => answers,
+            Err(desc) => {
+                println!("day {:>2}: FAILED: {}", day_num, desc);
+                failures += 1;
+                continue;
+            }
+        };
+        for test in inputs {
+            let input = runner::input_name(*test);
+            if !parts.iter().any(|part| answers.expected(input, *part).is_some()) {
+                continue;
+            }
+            let source = Input::Tree { root: String::from(root), test: *test };
+            let run = match runner::guarded_load_and_run(registered.maker, day_num, &source, parts, timeout) {
+                Ok(run) => run,
+                Err(desc) => {
+                    println!("day {:>2} {:<5}: FAILED: {}", day_num, input, desc);
+                    failures += 1;
+                    continue;
+                }
+            };
+            for PartRun { part, answer: actual, .. } in run.parts {
+                let expected = match answers.expected(input, part) {
+                    None => continue,
+                    Some(expected) => expected
+                };
+                checks += 1;
+                if actual == *expected {
+                    println!("day {:>2} {:<5} part {}: ok", day_num, input, part);
+                } else {
+                    failures += 1;
+                    println!("day {:>2} {:<5} part {}: MISMATCH", day_num, input, part);
+                    print_answer("- expected", expected);
+                    print_answer("+ actual", &actual);
+                }
+            }
+        }
+        let case_counts = cases::check_cases(registered, root, parts, timeout);
+        checks += case_counts.cases - case_counts.skipped;
+        failures += case_counts.failures;
+    }
+    println!("{} checks, {} failures", checks, failures);
+    failures == 0
+}
+
+fn parse_answer(word: &str) -> Answer {
+    if word == "n/a" {
+        Answer::NotApplicable
+    } else if let Ok(num) = word.parse::<i128>() {
+        num.into()
+    } else if word.len() > 1 && word.starts_with('"') && word.ends_with('"') {
+        Answer::from(&word[1..word.len() - 1])
+    } else {
+        Answer::from(word)
+    }
+}
+
+// one answers.txt entry, what AnswerKey::from_content reads back as the same answer
+pub fn format_entry(input: &str, part: usize, answer: &Answer) -> String {
+    match answer {
+        Answer::Str(word) => format!("{} {}: \"{}\"\n", input, part, word),
+        Answer::Grid(rows) => format!("{} {}: grid\n{}", input, part,
+                                      rows.iter().map(|row| format!("|{}\n", row)).collect::<String>()),
+        answer => format!("{} {}: {}\n", input, part, answer)
+    }
+}
+
+pub fn print_answer(label: &str, answer: &Answer) {
+    if answer.is_multiline() {
+        println!("    {}:", label);
+        answer.to_string().lines().for_each(|row| println!("      |{}", row));
+    } else {
+        println!("    {}: {}", label, answer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::day::Answer;
+    use super::{format_entry, AnswerKey};
+
+    const ANSWERS: &str = "\
+# <input> <part>: <answer>
+test 1: 17
+test 2: grid
+|#.#
+|.#.
+
+input 1: 170141183460469231731687303715884105727
+input 2: \"CEJKLUGJ\"
+";
+
+    #[test]
+    fn every_kind_of_answer() {
+        let key = AnswerKey::from_content(ANSWERS).unwrap();
+        assert_eq!(key.expected("test", 1), Some(&Answer::Int(17)));
+        assert_eq!(key.expected("test", 2), Some(&Answer::Grid(vec![String::from("#.#"), String::from(".#.")])));
+        assert_eq!(key.expected("input", 1), Some(&Answer::BigInt(i128::MAX)));
+        assert_eq!(key.expected("input", 2), Some(&Answer::from("CEJKLUGJ")));
+        assert_eq!(AnswerKey::from_content("test 2: n/a\n").unwrap().expected("test", 2), Some(&Answer::NotApplicable));
+    }
+
+    #[test]
+    fn grid_at_the_end_and_missing_parts() {
+        let key = AnswerKey::from_content("input 2: grid\n|##\n|#.").unwrap();
+        assert_eq!(key.expected("input", 2), Some(&Answer::Grid(vec![String::from("##"), String::from("#.")])));
+        assert_eq!(key.expected("input", 1), None);
+        assert_eq!(key.expected("test", 2), None);
+    }
+
+    #[test]
+    fn malformed_lines_say_where() {
+        let error = |content: &str| AnswerKey::from_content(content).err().unwrap();
+        assert_eq!(error("test 1: 4\n|#.\n"), "line 2: grid row outside of a grid answer");
+        assert_eq!(error("test 1 4\n"), "line 1: expected \"<input> <part>: <answer>\"");
+        assert_eq!(error("test: 4\n"), "line 1: expected \"<input> <part>\"");
+        assert_eq!(error("\ntest x: 4\n"), "line 2: part is not a num: x");
+        assert_eq!(error("test 3: 4\n"), "line 1: no such part 3");
+    }
+
+    #[test]
+    fn entries_read_back_the_same() {
+        let answers = [Answer::Int(-3), Answer::BigInt(i128::MIN), Answer::from("4 words, one quote\""),
+                       Answer::Grid(vec![String::from(" #"), String::from("# ")]), Answer::NotApplicable];
+        for answer in answers {
+            let key = AnswerKey::from_content(&format_entry("input", 1, &answer)).unwrap();
+            assert_eq!(key.expected("input", 1), Some(&answer));
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use log::info;
+use super::cases;
+use super::registry::Registered;
+use super::runner::Input;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+// what a rerun depends on, each day's dayNN/*.txt and its test cases for the tree, or the one input file.
+// listed again on every poll so a file appearing or going away counts as a change
+fn watched_files(days: &[Registered], input: &Input) -> Vec<PathBuf> {
+    match input {
+        Input::Tree { root, .. } => days.iter()
+            .flat_map(|registered| [format!("{}/day{:02}", root, registered.day), cases::cases_dir(root, registered.day)])
+            .flat_map(|dir| fs::read_dir(dir).into_iter().flatten().flatten())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect(),
+        Input::File(path) => vec![PathBuf::from(path)],
+        Input::Stdin => Vec::new()
+    }
+}
+
+fn snapshot(files: &[PathBuf]) -> BTreeMap<PathBuf, Option<(SystemTime, u64)>> {
+    files.iter()
+        .map(|path| (path.clone(), fs::metadata(path).ok()
+            .and_then(|metadata| metadata.modified().ok().map(|modified| (modified, metadata.len())))))
+        .collect()
+}
+
+// clears the screen and reruns whenever a watched file changes, until interrupted
+pub fn watch<F: FnMut() -> bool>(days: &[Registered], input: &Input, mut rerun: F) -> Result<(), String> {
+    if let Input::Stdin = input {
+        return Err(String::from("stdin can not be watched"));
+    }
+    loop {
+        let files = watched_files(days, input);
+        let mut last = snapshot(&files);
+        print!("{}", CLEAR_SCREEN);
+        io::stdout().flush().ok();
+        let ok = rerun();
+        info!("{} at {}, watching {} files for changes", if ok { "ok" } else { "FAILED" },
+              chrono::Local::now().format("%H:%M:%S"), files.len());
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = snapshot(&watched_files(days, input));
+            if current == last {
+                continue;
+            }
+            // an editor's save can take more than one write, wait for it to settle
+            last = current;
+            thread::sleep(POLL_INTERVAL);
+            if snapshot(&watched_files(days, input)) == last {
+                break;
+            }
+        }
+    }
+}